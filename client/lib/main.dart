@@ -1,5 +1,4 @@
 import 'dart:io';
-import 'dart:math';
 import 'dart:typed_data';
 
 import 'package:flutter/material.dart';
@@ -109,9 +108,8 @@ class _ConnectedScreenState extends State<ConnectedScreen> {
                             max: 99,
                             onChanged: (v) => setState(() { 
                                 _brightnessValue = v; 
-                                var brightness = (v + pow(v, 2.5)) / 400;
-                                brightness = (v / 100) * 255;
-                                brightness = exp(v * 0.05545) - 1;
+                                // linear, the lights apply the brightness curve themselves
+                                var brightness = (v / 99) * 255;
                                 QuickBlue.writeValue(
                                     widget.deviceId, 
                                     serviceId, 
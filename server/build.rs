@@ -14,6 +14,12 @@ use std::io::Write;
 use std::path::PathBuf;
 
 fn main() {
+    // None of the linker scripts below exist when building for the host
+    // (e.g. `cargo test --no-default-features`), so only add them for the Pico.
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("none") {
+        return;
+    }
+
     // Put `memory.x` in our output directory and ensure it's
     // on the linker search path.
     let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
@@ -5,7 +5,8 @@ MEMORY {
     BOOT2 : ORIGIN = 0x10000000, LENGTH = 0x100
 
     /* Define the memory region for the application to be loaded next */
-    FLASH : ORIGIN = 0x10000100, LENGTH = 2048K - 0x100
+    /* The last 4K sector is left out, it holds the saved config */
+    FLASH : ORIGIN = 0x10000100, LENGTH = 2048K - 0x100 - 4K
 
     /* Define the memory region for SRAM */
     RAM   : ORIGIN = 0x20000000, LENGTH = 264K
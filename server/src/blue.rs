@@ -4,11 +4,27 @@ use embassy_sync::channel::Sender;
 use log::error;
 use log::info;
 
-use embassy_futures::select::select3;
+use core::sync::atomic::AtomicU16;
+use core::sync::atomic::Ordering;
+
+use embassy_futures::select::select;
+use embassy_futures::select::select4;
+use embassy_futures::select::Either;
 use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Duration;
+use embassy_time::Ticker;
+use embassy_time::Timer;
 use trouble_host::prelude::*;
 
+use crate::config::Config;
+use crate::config::Storage;
+use crate::led::PixelFormat;
+use crate::led::MAX_LEDS;
+use crate::led::MAX_STRIPS;
+use crate::lighting::Curve;
 use crate::lighting::Message;
+use crate::Blend;
 use crate::Color;
 
 /// Size of L2CAP packets (ATT MTU is this - 4)
@@ -20,7 +36,15 @@ const CONNECTIONS_MAX: usize = 1;
 /// Max number of L2CAP channels.
 const L2CAP_CHANNELS_MAX: usize = 2; // Signal + att
 
-const MAX_ATTRIBUTES: usize = 32;
+const MAX_ATTRIBUTES: usize = 64;
+
+/// How often the current draw characteristic gets updated
+const DRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the config has to stay the same before it gets saved. Saving
+/// erases a whole sector of flash and pauses the lighting core while it does,
+/// so dragging a slider in the app shouldn't save on every write.
+const SAVE_DELAY: Duration = Duration::from_secs(3);
 
 type Resources<C> = HostResources<C, CONNECTIONS_MAX, L2CAP_CHANNELS_MAX, L2CAP_MTU>;
 
@@ -34,6 +58,20 @@ struct Handles {
     skip: Characteristic,
     speed: Characteristic,
     animation: Characteristic,
+    length: Characteristic,
+    strip: Characteristic,
+    segment: Characteristic,
+    segment_range: Characteristic,
+    format: Characteristic,
+    curve: Characteristic,
+    power_budget: Characteristic,
+    draw: Characteristic,
+    temperature: Characteristic,
+    white_balance: Characteristic,
+    transition: Characteristic,
+    layer: Characteristic,
+    layer_blend: Characteristic,
+    frame_rate: Characteristic,
 }
 
 const fn gen_uuid(s: &str) -> Uuid {
@@ -53,7 +91,9 @@ const fn gen_uuid(s: &str) -> Uuid {
 
 pub async fn run<C: Controller, M: RawMutex, const N: usize>(
     controller: C,
-    sender: Sender<'_, M, Message, N>,
+    senders: &[Sender<'_, M, Message, N>],
+    draw: &[AtomicU16; MAX_STRIPS],
+    storage: &mut Storage<'_>,
 ) {
     let address = Address::random([0xff, 0x9f, 0x1a, 0x05, 0xe4, 0xff]);
     info!("Our address = {:?}", address);
@@ -84,6 +124,20 @@ pub async fn run<C: Controller, M: RawMutex, const N: usize>(
     let mut skip = [0u8];
     let mut animation_speed = [0u8; 4];
     let mut animation = [0u8; 16];
+    let mut length = [0u8; 2];
+    let mut strip = [0u8];
+    let mut segment = [0u8];
+    let mut segment_range = [0u8; 4];
+    let mut format = [0u8];
+    let mut curve = [0u8];
+    let mut power_budget = [0u8; 2];
+    let mut current_draw = [0u8; 2 * MAX_STRIPS];
+    let mut temperature = [0u8; 2];
+    let mut white_balance = [0u8; 3];
+    let mut transition = [0u8; 2];
+    let mut layer = [0u8];
+    let mut layer_blend = [0u8; 2];
+    let mut frame_rate = [0u8];
 
     let handles = {
         const SERVICE_UUID: Uuid = gen_uuid("michaels mansion");
@@ -92,6 +146,20 @@ pub async fn run<C: Controller, M: RawMutex, const N: usize>(
         const SKIP_UUID: Uuid = gen_uuid("skip");
         const ANIMATION_UUID: Uuid = gen_uuid("animation");
         const SPEED_UUID: Uuid = gen_uuid("speed");
+        const LENGTH_UUID: Uuid = gen_uuid("length");
+        const STRIP_UUID: Uuid = gen_uuid("strip");
+        const SEGMENT_UUID: Uuid = gen_uuid("segment");
+        const SEGMENT_RANGE_UUID: Uuid = gen_uuid("segment range");
+        const FORMAT_UUID: Uuid = gen_uuid("format");
+        const CURVE_UUID: Uuid = gen_uuid("curve");
+        const POWER_BUDGET_UUID: Uuid = gen_uuid("power budget");
+        const DRAW_UUID: Uuid = gen_uuid("current draw");
+        const TEMPERATURE_UUID: Uuid = gen_uuid("temperature");
+        const WHITE_BALANCE_UUID: Uuid = gen_uuid("white balance");
+        const TRANSITION_UUID: Uuid = gen_uuid("transition");
+        const LAYER_UUID: Uuid = gen_uuid("layer");
+        const LAYER_BLEND_UUID: Uuid = gen_uuid("layer blend");
+        const FRAME_RATE_UUID: Uuid = gen_uuid("frame rate");
 
         let mut service = table.add_service(Service::new(SERVICE_UUID));
 
@@ -127,6 +195,90 @@ pub async fn run<C: Controller, M: RawMutex, const N: usize>(
             .add_characteristic(ANIMATION_UUID, &[CharacteristicProp::Write], &mut animation)
             .build();
 
+        let length = service
+            .add_characteristic(LENGTH_UUID, &[CharacteristicProp::Write], &mut length)
+            .build();
+
+        let strip = service
+            .add_characteristic(STRIP_UUID, &[CharacteristicProp::Write], &mut strip)
+            .build();
+
+        let segment = service
+            .add_characteristic(SEGMENT_UUID, &[CharacteristicProp::Write], &mut segment)
+            .build();
+
+        let segment_range = service
+            .add_characteristic(
+                SEGMENT_RANGE_UUID,
+                &[CharacteristicProp::Write],
+                &mut segment_range,
+            )
+            .build();
+
+        let format = service
+            .add_characteristic(FORMAT_UUID, &[CharacteristicProp::Write], &mut format)
+            .build();
+
+        let curve = service
+            .add_characteristic(CURVE_UUID, &[CharacteristicProp::Write], &mut curve)
+            .build();
+
+        let power_budget = service
+            .add_characteristic(
+                POWER_BUDGET_UUID,
+                &[CharacteristicProp::Write],
+                &mut power_budget,
+            )
+            .build();
+
+        let draw = service
+            .add_characteristic(DRAW_UUID, &[CharacteristicProp::Read], &mut current_draw)
+            .build();
+
+        let temperature = service
+            .add_characteristic(
+                TEMPERATURE_UUID,
+                &[CharacteristicProp::Write],
+                &mut temperature,
+            )
+            .build();
+
+        let white_balance = service
+            .add_characteristic(
+                WHITE_BALANCE_UUID,
+                &[CharacteristicProp::Write],
+                &mut white_balance,
+            )
+            .build();
+
+        let transition = service
+            .add_characteristic(
+                TRANSITION_UUID,
+                &[CharacteristicProp::Write],
+                &mut transition,
+            )
+            .build();
+
+        let layer = service
+            .add_characteristic(LAYER_UUID, &[CharacteristicProp::Write], &mut layer)
+            .build();
+
+        let layer_blend = service
+            .add_characteristic(
+                LAYER_BLEND_UUID,
+                &[CharacteristicProp::Write],
+                &mut layer_blend,
+            )
+            .build();
+
+        let frame_rate = service
+            .add_characteristic(
+                FRAME_RATE_UUID,
+                &[CharacteristicProp::Write],
+                &mut frame_rate,
+            )
+            .build();
+
         service.build();
 
         Handles {
@@ -135,16 +287,34 @@ pub async fn run<C: Controller, M: RawMutex, const N: usize>(
             skip,
             speed,
             animation,
+            length,
+            strip,
+            segment,
+            segment_range,
+            format,
+            curve,
+            power_budget,
+            draw,
+            temperature,
+            white_balance,
+            transition,
+            layer,
+            layer_blend,
+            frame_rate,
         }
     };
 
     let server = Server::new(stack, &mut table);
 
+    let config = storage.load();
+    let config_changed = Signal::new();
+
     info!("Starting advertising and GATT service");
-    let _ = select3(
+    let _ = select4(
         ble_task(runner),
-        gatt_task(&server, sender, handles),
+        gatt_task(&server, senders, draw, handles, config, &config_changed),
         advertise_task(peripheral),
+        save_task(storage, config, &config_changed),
     )
     .await;
 }
@@ -157,11 +327,36 @@ async fn ble_task<C: Controller>(mut runner: Runner<'_, C>) {
 
 async fn gatt_task<C: Controller, M: RawMutex, const N: usize>(
     server: &Server<'_, '_, C>,
-    sender: Sender<'_, M, Message, N>,
+    senders: &[Sender<'_, M, Message, N>],
+    draw: &[AtomicU16; MAX_STRIPS],
     handles: Handles,
+    mut config: Config,
+    config_changed: &Signal<NoopRawMutex, Config>,
 ) {
+    // every other characteristic applies to whichever strip was picked last
+    let mut strip = 0;
+    let mut sender = senders[strip];
+    // kept across events, so a client that never stops writing still sees
+    // the current draw go up every interval
+    let mut draw_ticker = Ticker::every(DRAW_INTERVAL);
+
     loop {
-        match server.next().await {
+        let event = match select(server.next(), draw_ticker.next()).await {
+            Either::First(event) => event,
+            Either::Second(()) => {
+                // milliamps of every strip, u16 LE each
+                let mut value = [0u8; 2 * MAX_STRIPS];
+                for (chunk, draw) in value.chunks_exact_mut(2).zip(draw) {
+                    chunk.copy_from_slice(&draw.load(Ordering::Relaxed).to_le_bytes());
+                }
+                if let Err(e) = server.set(handles.draw, &value) {
+                    error!("[gatt] failed to update current draw: {e:?}");
+                }
+                continue;
+            }
+        };
+
+        match event {
             Ok(GattEvent::Write {
                 handle,
                 connection: _,
@@ -215,6 +410,143 @@ async fn gatt_task<C: Controller, M: RawMutex, const N: usize>(
                         })
                         .unwrap()
                         .await;
+                } else if handle == handles.length {
+                    info!("setting length");
+                    let length = server
+                        .get(handles.length, |value| {
+                            value.try_into().map(u16::from_le_bytes).ok()
+                        })
+                        .unwrap();
+
+                    if let Some(length) = length {
+                        let length = length.clamp(1, MAX_LEDS as u16);
+                        sender.send(Message::SetLength(length)).await;
+
+                        config.lengths[strip] = length;
+                        config_changed.signal(config);
+                    }
+                } else if handle == handles.strip {
+                    let index = server
+                        .get(handles.strip, |value| value[0] as usize)
+                        .unwrap();
+                    if index < senders.len() {
+                        info!("selecting strip {index}");
+                        strip = index;
+                        sender = senders[strip];
+                    } else {
+                        error!("[gatt] no strip {index}");
+                    }
+                } else if handle == handles.segment {
+                    info!("selecting segment");
+                    server
+                        .get(handles.segment, |value| {
+                            sender.send(Message::SelectSegment(value[0]))
+                        })
+                        .unwrap()
+                        .await;
+                } else if handle == handles.segment_range {
+                    info!("setting segment range");
+                    server
+                        .get(handles.segment_range, |value| {
+                            let start = u16::from_le_bytes([value[0], value[1]]);
+                            let end = u16::from_le_bytes([value[2], value[3]]);
+                            sender.send(Message::SetSegment(start, end))
+                        })
+                        .unwrap()
+                        .await;
+                } else if handle == handles.format {
+                    let format = server
+                        .get(handles.format, |value| PixelFormat::from_byte(value[0]))
+                        .unwrap();
+
+                    if let Some(format) = format {
+                        // the PIO only gets set up at boot, so this just gets saved
+                        info!("setting format to {format:?}, takes effect after a restart");
+                        config.formats[strip] = format;
+                        config_changed.signal(config);
+                    }
+                } else if handle == handles.curve {
+                    info!("setting brightness curve");
+                    let curve = server
+                        .get(handles.curve, |value| Curve::from_byte(value[0]))
+                        .unwrap();
+                    sender.send(Message::SetCurve(curve)).await;
+
+                    config.curves[strip] = curve;
+                    config_changed.signal(config);
+                } else if handle == handles.power_budget {
+                    info!("setting power budget");
+                    let budget = server
+                        .get(handles.power_budget, |value| {
+                            value.try_into().map(u16::from_le_bytes).ok()
+                        })
+                        .unwrap();
+
+                    if let Some(budget) = budget {
+                        sender.send(Message::SetPowerBudget(budget)).await;
+
+                        config.budgets[strip] = budget;
+                        config_changed.signal(config);
+                    }
+                } else if handle == handles.temperature {
+                    info!("setting color temperature");
+                    server
+                        .get(handles.temperature, |value| {
+                            let kelvin = u16::from_le_bytes([value[0], value[1]]);
+                            sender.send(Message::SetTemperature(kelvin))
+                        })
+                        .unwrap()
+                        .await;
+                } else if handle == handles.white_balance {
+                    info!("setting white balance");
+                    let balance = server
+                        .get(handles.white_balance, |value| {
+                            Color::new(value[0], value[1], value[2])
+                        })
+                        .unwrap();
+                    sender.send(Message::SetWhiteBalance(balance)).await;
+
+                    config.white_balances[strip] = balance;
+                    config_changed.signal(config);
+                } else if handle == handles.transition {
+                    info!("setting transition");
+                    server
+                        .get(handles.transition, |value| {
+                            let millis = u16::from_le_bytes([value[0], value[1]]);
+                            sender.send(Message::SetTransition(millis))
+                        })
+                        .unwrap()
+                        .await;
+                } else if handle == handles.layer {
+                    info!("selecting layer");
+                    server
+                        .get(handles.layer, |value| {
+                            sender.send(Message::SelectLayer(value[0]))
+                        })
+                        .unwrap()
+                        .await;
+                } else if handle == handles.layer_blend {
+                    let layer = server
+                        .get(handles.layer_blend, |value| {
+                            Blend::from_byte(value[0]).map(|blend| (blend, value[1]))
+                        })
+                        .unwrap();
+
+                    match layer {
+                        Some((blend, opacity)) => {
+                            info!("setting layer blend");
+                            sender.send(Message::SetLayer(blend, opacity)).await;
+                        }
+                        None => error!("[gatt] unknown blend mode"),
+                    }
+                } else if handle == handles.frame_rate {
+                    info!("setting frame rate");
+                    server
+                        .get(handles.frame_rate, |value| {
+                            sender.send(Message::SetFrameRate(value[0]))
+                        })
+                        .unwrap()
+                        .await;
                 } else {
                     info!("[gatt] Write event on {:?}", handle);
                 }
@@ -232,6 +564,33 @@ async fn gatt_task<C: Controller, M: RawMutex, const N: usize>(
     }
 }
 
+/// Saves the config once it's gone `SAVE_DELAY` without changing, unless it's
+/// back to what's already saved. `saved` is what's in flash to begin with.
+async fn save_task(
+    storage: &mut Storage<'_>,
+    mut saved: Config,
+    config_changed: &Signal<NoopRawMutex, Config>,
+) {
+    loop {
+        let mut config = config_changed.wait().await;
+        // every change puts the save off again
+        while let Either::First(newer) =
+            select(config_changed.wait(), Timer::after(SAVE_DELAY)).await
+        {
+            config = newer;
+        }
+
+        if config == saved {
+            continue;
+        }
+        info!("[config] saving");
+        match storage.save(&config) {
+            Ok(()) => saved = config,
+            Err(e) => error!("[config] failed to save config: {e:?}"),
+        }
+    }
+}
+
 async fn advertise_task<C: Controller>(
     mut peripheral: Peripheral<'_, C>,
 ) -> Result<(), BleHostError<C::Error>> {
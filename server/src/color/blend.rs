@@ -0,0 +1,79 @@
+//! Ways of mixing two colors, channel by channel
+use crate::Color;
+
+impl Color {
+    /// `t` of the way from `self` to `other`, clamped to 0-1
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = (t.clamp(0., 1.) * 256.) as u16;
+        self.zip(other, |a, b| {
+            ((a as u16 * (256 - t) + b as u16 * t) >> 8) as u8
+        })
+    }
+
+    /// Both lights at once, clipped at full brightness
+    pub fn saturating_add(self, other: Color) -> Color {
+        self.zip(other, u8::saturating_add)
+    }
+
+    /// Only what both let through, e.g. white leaves a color alone and black
+    /// blacks it out
+    pub fn multiply(self, other: Color) -> Color {
+        self.zip(other, |a, b| ((a as u16 * b as u16 + 255) >> 8) as u8)
+    }
+
+    /// The inverse of multiply: brightens, but never past full
+    pub fn screen(self, other: Color) -> Color {
+        self.zip(other, |a, b| {
+            255 - (((255 - a) as u16 * (255 - b) as u16 + 255) >> 8) as u8
+        })
+    }
+
+    /// The brighter of each channel
+    pub fn max(self, other: Color) -> Color {
+        self.zip(other, u8::max)
+    }
+
+    fn zip(self, other: Color, f: impl Fn(u8, u8) -> u8) -> Color {
+        Color::new(
+            f(self.red(), other.red()),
+            f(self.green(), other.green()),
+            f(self.blue(), other.blue()),
+        )
+    }
+}
+
+/// How a color gets drawn over what's already there
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Blend {
+    /// Replaces what's underneath
+    #[default]
+    Normal,
+    Add,
+    Multiply,
+    Screen,
+    Max,
+}
+
+impl Blend {
+    /// `top` drawn over `base`
+    pub fn apply(self, base: Color, top: Color) -> Color {
+        match self {
+            Self::Normal => top,
+            Self::Add => base.saturating_add(top),
+            Self::Multiply => base.multiply(top),
+            Self::Screen => base.screen(top),
+            Self::Max => base.max(top),
+        }
+    }
+
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        Some(match byte {
+            0 => Self::Normal,
+            1 => Self::Add,
+            2 => Self::Multiply,
+            3 => Self::Screen,
+            4 => Self::Max,
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,172 @@
+//! Hue based color models, mostly for color pickers and hue sweeps. They're
+//! cheap, but not perceptual: see [`Oklch`](super::Oklch) for that.
+use crate::color::from_unit;
+use crate::color::lerp;
+use crate::color::lerp_hue;
+use crate::color::to_unit;
+use crate::color::wrap_hue;
+use crate::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsv {
+    /// In degrees, 0-360
+    pub hue: f32,
+    /// 0-1
+    pub saturation: f32,
+    /// 0-1
+    pub value: f32,
+}
+
+impl Hsv {
+    pub const fn new(hue: f32, saturation: f32, value: f32) -> Self {
+        Self {
+            hue,
+            saturation,
+            value,
+        }
+    }
+
+    /// Turn the hue by `degrees`, either way round
+    pub fn rotate(self, degrees: f32) -> Self {
+        Self {
+            hue: wrap_hue(self.hue + degrees),
+            ..self
+        }
+    }
+
+    /// `t` of the way from `self` to `other`, going round the shorter way
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            hue: lerp_hue(
+                (self.hue, self.saturation),
+                (other.hue, other.saturation),
+                t,
+            ),
+            saturation: lerp(self.saturation, other.saturation, t),
+            value: lerp(self.value, other.value, t),
+        }
+    }
+}
+
+impl From<Hsv> for Color {
+    fn from(hsv: Hsv) -> Self {
+        let chroma = hsv.value * hsv.saturation;
+        from_hue(hsv.hue, chroma, hsv.value - chroma)
+    }
+}
+
+impl From<Color> for Hsv {
+    fn from(color: Color) -> Self {
+        let (hue, max, min) = to_hue(color);
+        let saturation = if max > 0. { (max - min) / max } else { 0. };
+
+        Self::new(hue, saturation, max)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsl {
+    /// In degrees, 0-360
+    pub hue: f32,
+    /// 0-1
+    pub saturation: f32,
+    /// 0-1, where 0.5 is the most colorful
+    pub lightness: f32,
+}
+
+impl Hsl {
+    pub const fn new(hue: f32, saturation: f32, lightness: f32) -> Self {
+        Self {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+
+    /// Turn the hue by `degrees`, either way round
+    pub fn rotate(self, degrees: f32) -> Self {
+        Self {
+            hue: wrap_hue(self.hue + degrees),
+            ..self
+        }
+    }
+
+    /// `t` of the way from `self` to `other`, going round the shorter way
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            hue: lerp_hue(
+                (self.hue, self.saturation),
+                (other.hue, other.saturation),
+                t,
+            ),
+            saturation: lerp(self.saturation, other.saturation, t),
+            lightness: lerp(self.lightness, other.lightness, t),
+        }
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(hsl: Hsl) -> Self {
+        let chroma = (1. - (2. * hsl.lightness - 1.).abs()) * hsl.saturation;
+        from_hue(hsl.hue, chroma, hsl.lightness - chroma / 2.)
+    }
+}
+
+impl From<Color> for Hsl {
+    fn from(color: Color) -> Self {
+        let (hue, max, min) = to_hue(color);
+        let lightness = (max + min) / 2.;
+        let saturation = if max > min {
+            (max - min) / (1. - (2. * lightness - 1.).abs())
+        } else {
+            0.
+        };
+
+        Self::new(hue, saturation, lightness)
+    }
+}
+
+/// The color `chroma` away from gray at `hue`, with `offset` added to every channel
+fn from_hue(hue: f32, chroma: f32, offset: f32) -> Color {
+    let sector = wrap_hue(hue) / 60.;
+    let x = chroma * (1. - (sector % 2. - 1.).abs());
+
+    let (red, green, blue) = match sector as u8 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+
+    Color::new(
+        from_unit(red + offset),
+        from_unit(green + offset),
+        from_unit(blue + offset),
+    )
+}
+
+/// Hue of `color` along with its largest and smallest channel, all 0-1
+fn to_hue(color: Color) -> (f32, f32, f32) {
+    let (red, green, blue) = (
+        to_unit(color.red()),
+        to_unit(color.green()),
+        to_unit(color.blue()),
+    );
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let delta = max - min;
+
+    let hue = if delta == 0. {
+        0.
+    } else if max == red {
+        60. * ((green - blue) / delta)
+    } else if max == green {
+        60. * ((blue - red) / delta + 2.)
+    } else {
+        60. * ((red - green) / delta + 4.)
+    };
+
+    (wrap_hue(hue), max, min)
+}
@@ -0,0 +1,43 @@
+//! Whites by color temperature, from candlelight to overcast sky
+use crate::Color;
+
+impl Color {
+    /// Warmest temperature `from_kelvin` goes down to
+    pub const MIN_KELVIN: u16 = 1000;
+    /// Coolest temperature `from_kelvin` goes up to
+    pub const MAX_KELVIN: u16 = 40000;
+
+    /// The color of a black body glowing at `kelvin`, clamped to
+    /// `MIN_KELVIN`-`MAX_KELVIN`. Around 2700K is a warm bulb and 6500K is
+    /// daylight.
+    ///
+    /// Uses Tanner Helland's fit to the CIE 1964 black body data, which is
+    /// close enough for lighting a room. LEDs differ in how blue their blue
+    /// is, so strips need their white balance set to match.
+    pub fn from_kelvin(kelvin: u16) -> Color {
+        let temp = kelvin.clamp(Self::MIN_KELVIN, Self::MAX_KELVIN) as f32 / 100.;
+
+        let red = if temp <= 66. {
+            255.
+        } else {
+            329.69873 * libm::powf(temp - 60., -0.13320476)
+        };
+
+        let green = if temp <= 66. {
+            99.4708 * libm::logf(temp) - 161.11957
+        } else {
+            288.12217 * libm::powf(temp - 60., -0.07551485)
+        };
+
+        let blue = if temp >= 66. {
+            255.
+        } else if temp <= 19. {
+            0.
+        } else {
+            138.51773 * libm::logf(temp - 10.) - 305.0448
+        };
+
+        let channel = |x: f32| (x.clamp(0., 255.) + 0.5) as u8;
+        Color::new(channel(red), channel(green), channel(blue))
+    }
+}
@@ -1,4 +1,18 @@
-#[derive(Clone, Copy, Debug)]
+mod blend;
+mod hsv;
+mod kelvin;
+mod oklab;
+mod palette;
+
+pub use blend::Blend;
+pub use hsv::Hsl;
+pub use hsv::Hsv;
+pub use oklab::Oklab;
+pub use oklab::Oklch;
+pub use palette::Palette;
+pub use palette::Stop;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Color {
     red: u8,
     green: u8,
@@ -65,9 +79,51 @@ impl Color {
 
         Self::new(red, green, blue)
     }
+}
+
+/// Below this chroma or saturation a color is gray enough that its hue means nothing
+const ACHROMATIC: f32 = 1e-4;
+
+/// A channel as 0-1
+fn to_unit(channel: u8) -> f32 {
+    channel as f32 / 255.
+}
+
+/// A 0-1 channel as a byte, clipping anything outside that
+fn from_unit(x: f32) -> u8 {
+    (x.clamp(0., 1.) * 255. + 0.5) as u8
+}
 
-    // green, red, blue, ???
-    pub const fn as_u32(self) -> u32 {
-        ((self.green as u32) << 24) | ((self.red as u32) << 16) | ((self.blue as u32) << 8)
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+/// Any angle in degrees as 0-360
+fn wrap_hue(hue: f32) -> f32 {
+    let hue = hue % 360.;
+    if hue < 0. {
+        hue + 360.
+    } else {
+        hue
     }
 }
+
+/// Interpolate `(hue, chroma)` pairs the shorter way round. Grays have no hue
+/// of their own, so they take the other one's instead of dragging the blend
+/// through red at 0°.
+fn lerp_hue((from, from_chroma): (f32, f32), (to, to_chroma): (f32, f32), t: f32) -> f32 {
+    let (from, to) = match (from_chroma < ACHROMATIC, to_chroma < ACHROMATIC) {
+        (true, false) => (to, to),
+        (false, true) => (from, from),
+        _ => (from, to),
+    };
+
+    let mut delta = (to - from) % 360.;
+    if delta > 180. {
+        delta -= 360.;
+    } else if delta < -180. {
+        delta += 360.;
+    }
+
+    wrap_hue(from + delta * t)
+}
@@ -0,0 +1,161 @@
+//! OKLab and its polar form OKLCH, perceptual color spaces by Björn Ottosson
+//! (<https://bottosson.github.io/posts/oklab/>). Equal steps look equally far
+//! apart, so blending through them doesn't go muddy or dip in brightness the
+//! way RGB and HSV do.
+use crate::color::from_unit;
+use crate::color::lerp;
+use crate::color::lerp_hue;
+use crate::color::to_unit;
+use crate::color::wrap_hue;
+use crate::color::ACHROMATIC;
+use crate::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklab {
+    /// Perceived lightness, 0-1
+    pub l: f32,
+    /// Green (negative) to red (positive), roughly -0.4-0.4
+    pub a: f32,
+    /// Blue (negative) to yellow (positive), roughly -0.4-0.4
+    pub b: f32,
+}
+
+impl Oklab {
+    pub const fn new(l: f32, a: f32, b: f32) -> Self {
+        Self { l, a, b }
+    }
+
+    /// `t` of the way from `self` to `other` in a straight line
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            l: lerp(self.l, other.l, t),
+            a: lerp(self.a, other.a, t),
+            b: lerp(self.b, other.b, t),
+        }
+    }
+}
+
+impl From<Color> for Oklab {
+    fn from(color: Color) -> Self {
+        let red = srgb_to_linear(to_unit(color.red()));
+        let green = srgb_to_linear(to_unit(color.green()));
+        let blue = srgb_to_linear(to_unit(color.blue()));
+
+        let l = libm::cbrtf(0.41222147 * red + 0.53633254 * green + 0.051445993 * blue);
+        let m = libm::cbrtf(0.2119035 * red + 0.6806995 * green + 0.10739696 * blue);
+        let s = libm::cbrtf(0.08830246 * red + 0.28171884 * green + 0.6299787 * blue);
+
+        Self {
+            l: 0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+            a: 1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+            b: 0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+        }
+    }
+}
+
+/// Colors outside what the LEDs can show get clipped channel by channel
+impl From<Oklab> for Color {
+    fn from(lab: Oklab) -> Self {
+        let l = lab.l + 0.39633778 * lab.a + 0.21580376 * lab.b;
+        let m = lab.l - 0.105561346 * lab.a - 0.06385417 * lab.b;
+        let s = lab.l - 0.08948418 * lab.a - 1.2914855 * lab.b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+
+        let red = 4.0767417 * l - 3.3077116 * m + 0.23096994 * s;
+        let green = -1.268438 * l + 2.6097574 * m - 0.34131938 * s;
+        let blue = -0.0041960864 * l - 0.7034186 * m + 1.7076147 * s;
+
+        Color::new(
+            from_unit(linear_to_srgb(red)),
+            from_unit(linear_to_srgb(green)),
+            from_unit(linear_to_srgb(blue)),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklch {
+    /// Perceived lightness, 0-1
+    pub l: f32,
+    /// How colorful, 0 for gray and rarely above 0.37
+    pub c: f32,
+    /// In degrees, 0-360
+    pub h: f32,
+}
+
+impl Oklch {
+    pub const fn new(l: f32, c: f32, h: f32) -> Self {
+        Self { l, c, h }
+    }
+
+    /// Turn the hue by `degrees`, either way round, without changing how
+    /// bright or colorful it looks
+    pub fn rotate(self, degrees: f32) -> Self {
+        Self {
+            h: wrap_hue(self.h + degrees),
+            ..self
+        }
+    }
+
+    /// `t` of the way from `self` to `other`, going round the shorter way
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            l: lerp(self.l, other.l, t),
+            c: lerp(self.c, other.c, t),
+            h: lerp_hue((self.h, self.c), (other.h, other.c), t),
+        }
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(lab: Oklab) -> Self {
+        let c = libm::hypotf(lab.a, lab.b);
+        let h = if c < ACHROMATIC {
+            0.
+        } else {
+            wrap_hue(libm::atan2f(lab.b, lab.a).to_degrees())
+        };
+
+        Self { l: lab.l, c, h }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(lch: Oklch) -> Self {
+        let h = lch.h.to_radians();
+        Self {
+            l: lch.l,
+            a: lch.c * libm::cosf(h),
+            b: lch.c * libm::sinf(h),
+        }
+    }
+}
+
+impl From<Color> for Oklch {
+    fn from(color: Color) -> Self {
+        Oklab::from(color).into()
+    }
+}
+
+/// Colors outside what the LEDs can show get clipped channel by channel
+impl From<Oklch> for Color {
+    fn from(lch: Oklch) -> Self {
+        Oklab::from(lch).into()
+    }
+}
+
+fn srgb_to_linear(x: f32) -> f32 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        libm::powf((x + 0.055) / 1.055, 2.4)
+    }
+}
+
+fn linear_to_srgb(x: f32) -> f32 {
+    if x <= 0.0031308 {
+        x * 12.92
+    } else {
+        1.055 * libm::powf(x, 1. / 2.4) - 0.055
+    }
+}
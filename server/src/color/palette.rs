@@ -0,0 +1,81 @@
+//! Gradients through any number of colors
+use crate::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stop {
+    /// Where along the gradient, 0-1
+    pub position: f32,
+    pub color: Color,
+}
+
+impl Stop {
+    pub const fn new(position: f32, color: Color) -> Self {
+        Self { position, color }
+    }
+}
+
+/// A gradient through `N` stops, blending in RGB between neighbouring ones
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette<const N: usize> {
+    stops: [Stop; N],
+}
+
+impl<const N: usize> Palette<N> {
+    /// `stops` have to be in order of position
+    pub const fn new(stops: [Stop; N]) -> Self {
+        assert!(N > 0, "a palette needs at least one stop");
+        Self { stops }
+    }
+
+    /// Spreads `colors` out evenly, with the first at 0 and the last at 1
+    pub fn even(colors: [Color; N]) -> Self {
+        let step = 1. / (N.max(2) - 1) as f32;
+        let mut position = 0.;
+        Self::new(colors.map(|color| {
+            let stop = Stop::new(position, color);
+            position += step;
+            stop
+        }))
+    }
+
+    pub fn stops(&self) -> &[Stop; N] {
+        &self.stops
+    }
+
+    /// The color at `position`, clamped to 0-1. Before the first stop and
+    /// after the last, their colors carry on.
+    pub fn sample(&self, position: f32) -> Color {
+        let position = position.clamp(0., 1.);
+
+        let after = self.stops.partition_point(|stop| stop.position <= position);
+        if after == 0 {
+            return self.stops[0].color;
+        }
+        if after == N {
+            return self.stops[N - 1].color;
+        }
+
+        let (from, to) = (self.stops[after - 1], self.stops[after]);
+        let t = (position - from.position) / (to.position - from.position);
+        from.color.lerp(to.color, t)
+    }
+
+    /// Like `sample`, but wrapping around so that 1 blends back into the
+    /// first stop, for effects that scroll
+    pub fn sample_wrapping(&self, position: f32) -> Color {
+        let (first, last) = (self.stops[0], self.stops[N - 1]);
+
+        // somewhere in first..first + 1, so the gap between the last stop
+        // and the first one is all in one piece
+        let mut position = position - libm::floorf(position);
+        if position < first.position {
+            position += 1.;
+        }
+        if position <= last.position {
+            return self.sample(position);
+        }
+
+        let t = (position - last.position) / (1. + first.position - last.position);
+        last.color.lerp(first.color, t)
+    }
+}
@@ -0,0 +1,45 @@
+use embassy_rp::flash::Blocking;
+use embassy_rp::flash::Error;
+use embassy_rp::flash::Flash;
+use embassy_rp::flash::ERASE_SIZE;
+use embassy_rp::peripherals::FLASH;
+use embassy_rp::Peripheral;
+use log::warn;
+
+use super::Config;
+
+const FLASH_SIZE: usize = 2 * 1024 * 1024;
+
+/// The config lives in the last sector of flash, which `memory.x` keeps the
+/// firmware out of.
+const CONFIG_OFFSET: u32 = (FLASH_SIZE - ERASE_SIZE) as u32;
+
+pub struct Storage<'d> {
+    flash: Flash<'d, FLASH, Blocking, FLASH_SIZE>,
+}
+
+impl<'d> Storage<'d> {
+    pub fn new(flash: impl Peripheral<P = FLASH> + 'd) -> Self {
+        Self {
+            flash: Flash::new_blocking(flash),
+        }
+    }
+
+    /// The saved config, or the default one if nothing valid has been saved
+    pub fn load(&mut self) -> Config {
+        let mut bytes = [0; Config::SIZE];
+        if let Err(e) = self.flash.blocking_read(CONFIG_OFFSET, &mut bytes) {
+            warn!("[config] failed to read config: {e:?}");
+            return Config::default();
+        }
+
+        Config::from_bytes(bytes).unwrap_or_default()
+    }
+
+    /// Pauses the other core while the sector is rewritten
+    pub fn save(&mut self, config: &Config) -> Result<(), Error> {
+        self.flash
+            .blocking_erase(CONFIG_OFFSET, CONFIG_OFFSET + ERASE_SIZE as u32)?;
+        self.flash.blocking_write(CONFIG_OFFSET, &config.to_bytes())
+    }
+}
@@ -0,0 +1,141 @@
+//! Settings that survive a reboot
+#[cfg(feature = "rp2040")]
+mod flash;
+#[cfg(feature = "rp2040")]
+pub use flash::Storage;
+
+use crate::led::PixelFormat;
+use crate::led::DEFAULT_LEDS;
+use crate::led::MAX_LEDS;
+use crate::led::MAX_STRIPS;
+use crate::lighting::Curve;
+use crate::lighting::DEFAULT_BUDGET_MA;
+use crate::Color;
+
+/// Marks saved bytes as a `Config`. Change it whenever the layout changes so
+/// old configs get thrown out instead of misread.
+const MAGIC: [u8; 4] = *b"mlc6";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    /// How many LEDs are on each strip
+    pub lengths: [u16; MAX_STRIPS],
+    /// What kind of LEDs each strip has. Only read at boot, since the PIO
+    /// has to be set up for it.
+    pub formats: [PixelFormat; MAX_STRIPS],
+    /// The brightness curve of each strip
+    pub curves: [Curve; MAX_STRIPS],
+    /// How many milliamps each strip may draw, `0` for no limit
+    pub budgets: [u16; MAX_STRIPS],
+    /// Per channel gain of each strip, `Color::WHITE` for none
+    pub white_balances: [Color; MAX_STRIPS],
+}
+
+const LENGTHS: usize = MAGIC.len();
+const FORMATS: usize = LENGTHS + 2 * MAX_STRIPS;
+const CURVES: usize = FORMATS + MAX_STRIPS;
+const BUDGETS: usize = CURVES + MAX_STRIPS;
+const WHITE_BALANCES: usize = BUDGETS + 2 * MAX_STRIPS;
+
+impl Config {
+    /// Size of a config once serialized
+    pub const SIZE: usize = WHITE_BALANCES + 3 * MAX_STRIPS;
+
+    pub const fn new() -> Self {
+        Self {
+            lengths: [DEFAULT_LEDS as u16; MAX_STRIPS],
+            formats: [PixelFormat::WS2812; MAX_STRIPS],
+            curves: [Curve::Cie; MAX_STRIPS],
+            budgets: [DEFAULT_BUDGET_MA; MAX_STRIPS],
+            white_balances: [Color::WHITE; MAX_STRIPS],
+        }
+    }
+
+    pub fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[..LENGTHS].copy_from_slice(&MAGIC);
+        for (chunk, length) in bytes[LENGTHS..FORMATS]
+            .chunks_exact_mut(2)
+            .zip(self.lengths)
+        {
+            chunk.copy_from_slice(&length.to_le_bytes());
+        }
+        for (byte, format) in bytes[FORMATS..CURVES].iter_mut().zip(self.formats) {
+            *byte = format.to_byte();
+        }
+        for (byte, curve) in bytes[CURVES..BUDGETS].iter_mut().zip(self.curves) {
+            *byte = curve.to_byte();
+        }
+        for (chunk, budget) in bytes[BUDGETS..WHITE_BALANCES]
+            .chunks_exact_mut(2)
+            .zip(self.budgets)
+        {
+            chunk.copy_from_slice(&budget.to_le_bytes());
+        }
+        for (chunk, balance) in bytes[WHITE_BALANCES..]
+            .chunks_exact_mut(3)
+            .zip(self.white_balances)
+        {
+            chunk.copy_from_slice(&[balance.red(), balance.green(), balance.blue()]);
+        }
+        bytes
+    }
+
+    /// Returns `None` if `bytes` isn't a valid config, e.g. erased flash
+    pub fn from_bytes(bytes: [u8; Self::SIZE]) -> Option<Self> {
+        if bytes[..LENGTHS] != MAGIC {
+            return None;
+        }
+
+        let mut lengths = [0; MAX_STRIPS];
+        for (length, chunk) in lengths
+            .iter_mut()
+            .zip(bytes[LENGTHS..FORMATS].chunks_exact(2))
+        {
+            *length = u16::from_le_bytes([chunk[0], chunk[1]]);
+            if !(1..=MAX_LEDS as u16).contains(length) {
+                return None;
+            }
+        }
+
+        let mut formats = [PixelFormat::WS2812; MAX_STRIPS];
+        for (format, byte) in formats.iter_mut().zip(&bytes[FORMATS..CURVES]) {
+            *format = PixelFormat::from_byte(*byte)?;
+        }
+
+        let mut curves = [Curve::Cie; MAX_STRIPS];
+        for (curve, byte) in curves.iter_mut().zip(&bytes[CURVES..BUDGETS]) {
+            *curve = Curve::from_byte(*byte);
+        }
+
+        let mut budgets = [0; MAX_STRIPS];
+        for (budget, chunk) in budgets
+            .iter_mut()
+            .zip(bytes[BUDGETS..WHITE_BALANCES].chunks_exact(2))
+        {
+            *budget = u16::from_le_bytes([chunk[0], chunk[1]]);
+        }
+
+        let mut white_balances = [Color::WHITE; MAX_STRIPS];
+        for (balance, chunk) in white_balances
+            .iter_mut()
+            .zip(bytes[WHITE_BALANCES..].chunks_exact(3))
+        {
+            *balance = Color::new(chunk[0], chunk[1], chunk[2]);
+        }
+
+        Some(Self {
+            lengths,
+            formats,
+            curves,
+            budgets,
+            white_balances,
+        })
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,136 @@
+//! How colors are laid out on the wire for different kinds of strips
+use embassy_time::Duration;
+
+use super::PIO_HZ;
+use crate::Color;
+
+/// PIO cycles the driver takes to send a 1 bit. A 0 bit only takes 9, but
+/// frames are timed as if every bit were a 1 so that bright frames don't eat
+/// into the reset latch after them.
+const WORST_BIT_CYCLES: u64 = 11;
+
+/// The order a strip expects the red, green and blue bytes in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelOrder {
+    Rgb,
+    Rbg,
+    Grb,
+    Gbr,
+    Brg,
+    Bgr,
+}
+
+impl ChannelOrder {
+    const ALL: [Self; 6] = [
+        Self::Rgb,
+        Self::Rbg,
+        Self::Grb,
+        Self::Gbr,
+        Self::Brg,
+        Self::Bgr,
+    ];
+
+    /// The color's channels in this order
+    pub const fn arrange(self, color: Color) -> [u8; 3] {
+        let (r, g, b) = (color.red(), color.green(), color.blue());
+        match self {
+            Self::Rgb => [r, g, b],
+            Self::Rbg => [r, b, g],
+            Self::Grb => [g, r, b],
+            Self::Gbr => [g, b, r],
+            Self::Brg => [b, r, g],
+            Self::Bgr => [b, g, r],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelFormat {
+    pub order: ChannelOrder,
+    /// Whether the strip has a fourth, white, channel after the other three
+    pub rgbw: bool,
+}
+
+impl PixelFormat {
+    /// WS2812B and most other cheap strips
+    pub const WS2812: Self = Self {
+        order: ChannelOrder::Grb,
+        rgbw: false,
+    };
+
+    /// SK6812 RGBW strips
+    pub const SK6812: Self = Self {
+        order: ChannelOrder::Grb,
+        rgbw: true,
+    };
+
+    /// How many bits one pixel takes on the wire
+    pub const fn bits(self) -> u8 {
+        if self.rgbw {
+            32
+        } else {
+            24
+        }
+    }
+
+    /// The longest `len` pixels can take to clock out, not counting the
+    /// reset latch after them
+    pub const fn send_time(self, len: usize) -> Duration {
+        let cycles = len as u64 * self.bits() as u64 * WORST_BIT_CYCLES;
+        Duration::from_nanos(cycles * 1_000_000_000 / PIO_HZ)
+    }
+
+    /// Pack `color` for the PIO, which shifts out the top `bits()` bits, MSB first.
+    ///
+    /// On RGBW strips the white channel takes over whatever the three colors
+    /// have in common, which is brighter and whiter than mixing it.
+    pub const fn pack(self, color: Color) -> u32 {
+        let (color, white) = if self.rgbw {
+            let white = min(min(color.red(), color.green()), color.blue());
+            let color = Color::new(
+                color.red() - white,
+                color.green() - white,
+                color.blue() - white,
+            );
+            (color, white)
+        } else {
+            (color, 0)
+        };
+
+        let [a, b, c] = self.order.arrange(color);
+        u32::from_be_bytes([a, b, c, white])
+    }
+
+    /// Packs into a single byte for the BLE protocol and saved config: the
+    /// `ChannelOrder` in the low bits, and `0x80` set for RGBW
+    pub const fn to_byte(self) -> u8 {
+        let rgbw = if self.rgbw { 0x80 } else { 0 };
+        self.order as u8 | rgbw
+    }
+
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        let order = (byte & 0x7f) as usize;
+        if order >= ChannelOrder::ALL.len() {
+            return None;
+        }
+
+        Some(Self {
+            order: ChannelOrder::ALL[order],
+            rgbw: byte & 0x80 != 0,
+        })
+    }
+}
+
+impl Default for PixelFormat {
+    fn default() -> Self {
+        Self::WS2812
+    }
+}
+
+const fn min(a: u8, b: u8) -> u8 {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
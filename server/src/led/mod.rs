@@ -0,0 +1,78 @@
+//! LED strip output
+mod format;
+#[cfg(feature = "rp2040")]
+mod pio;
+pub use format::ChannelOrder;
+pub use format::PixelFormat;
+#[cfg(feature = "rp2040")]
+pub use pio::LedDriver;
+
+use embassy_time::Duration;
+
+use crate::Color;
+
+/// What the LED driver's PIO state machines are clocked at
+pub const PIO_HZ: u64 = 8_000_000;
+
+/// How long a strip's data line has to stay low after a frame before the
+/// strip latches it. Older WS2812s only need 50µs, newer ones need 280µs.
+pub const RESET_TIME: Duration = Duration::from_micros(280);
+
+/// The longest strip we have buffers for
+pub const MAX_LEDS: usize = 300;
+
+/// How many strips can be driven at once, one per PIO state machine
+pub const MAX_STRIPS: usize = 4;
+
+/// How many LEDs a strip has until told otherwise
+pub const DEFAULT_LEDS: usize = 90;
+
+/// Anything the lighting task can push pixels into.
+#[allow(async_fn_in_trait)]
+pub trait PixelSink {
+    /// Send a whole frame to the strip, in strip order. Never more than
+    /// `MAX_LEDS` pixels long.
+    async fn write(&mut self, pixels: &[Color]);
+}
+
+/// A sink that keeps the pixels in memory instead of lighting anything up.
+/// Useful for running the lighting code on the host.
+pub struct RecordingSink {
+    last: [Color; MAX_LEDS],
+    len: usize,
+    frames: usize,
+}
+
+impl RecordingSink {
+    pub const fn new() -> Self {
+        Self {
+            last: [Color::BLACK; MAX_LEDS],
+            len: 0,
+            frames: 0,
+        }
+    }
+
+    /// How many frames have been sent so far
+    pub const fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// The most recent frame, if there is one
+    pub fn last_frame(&self) -> Option<&[Color]> {
+        (self.frames != 0).then_some(&self.last[..self.len])
+    }
+}
+
+impl Default for RecordingSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PixelSink for RecordingSink {
+    async fn write(&mut self, pixels: &[Color]) {
+        self.last[..pixels.len()].copy_from_slice(pixels);
+        self.len = pixels.len();
+        self.frames += 1;
+    }
+}
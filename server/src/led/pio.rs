@@ -0,0 +1,123 @@
+use embassy_futures::yield_now;
+use embassy_rp::dma::AnyChannel;
+use embassy_rp::dma::Channel;
+use embassy_rp::gpio::Level;
+use embassy_rp::gpio::Output;
+
+use embassy_rp::pio;
+use embassy_rp::pio::Common;
+use embassy_rp::pio::Instance;
+use embassy_rp::pio::PioPin;
+use embassy_rp::pio::ShiftDirection;
+use embassy_rp::pio::StateMachine;
+use embassy_rp::Peripheral;
+use embassy_rp::PeripheralRef;
+use embassy_time::Instant;
+use embassy_time::Timer;
+
+// use embassy_rp::pio::{self, Pio, PioPin, Instance, StateMachineTx};
+
+use fixed::traits::ToFixed;
+use fixed_macro::types::U56F8;
+
+use super::PixelFormat;
+use super::PixelSink;
+use super::MAX_LEDS;
+use super::PIO_HZ;
+use super::RESET_TIME;
+use crate::Color;
+
+/// Sends frames to a strip over DMA, so the next frame can be rendered
+/// while the last one is still being clocked out
+pub struct LedDriver<'peripherals, PIO: Instance, const SM: usize> {
+    sm: StateMachine<'peripherals, PIO, SM>,
+    dma: PeripheralRef<'peripherals, AnyChannel>,
+    /// Packed pixels for the DMA to read from. Has to outlive any transfer,
+    /// which is why it's borrowed rather than part of the driver.
+    buffer: &'peripherals mut [u32; MAX_LEDS],
+    format: PixelFormat,
+    /// When the last frame will have been clocked out and latched
+    ready_at: Instant,
+}
+
+impl<'peripheral, PIO: Instance, const SM: usize> LedDriver<'peripheral, PIO, SM> {
+    pub fn new(
+        common: &mut Common<'peripheral, PIO>,
+        mut sm: StateMachine<'peripheral, PIO, SM>,
+        pin: impl PioPin,
+        dma: impl Peripheral<P = impl Channel> + 'peripheral,
+        buffer: &'peripheral mut [u32; MAX_LEDS],
+        format: PixelFormat,
+    ) -> Self {
+        let prg = pio_proc::pio_asm!(
+            ".side_set 1",
+            ".wrap_target",
+            "bitloop:",
+            "out x, 1 side 0 [3]", // set low 4 cycles (0.500us)
+            "jmp !x, do_zero side 1 [1]", // set high 2 cycle (0.250us)
+            "jmp bitloop side 1 [4]", // set high 5 cycles (0.625us)
+            "do_zero:",
+            "nop side 0 [2]" // set low 3 cycles (0.375us)
+                             // "out x, 1", // wait for data and read a bit into x (pin 1 should be set low)
+                             // "set pins, 1 [1]", // set pin high (0.4us)
+                             // "mov pins, x [1]", // set pin to x (0.4us)
+                             // "set pins, 0", // set pin low (0.2us + 0.2us during `out x, 1`)
+            ".wrap"
+        );
+        let mut cfg = pio::Config::default();
+
+        let mut raw_pin = pin.into_ref();
+        Output::new(raw_pin.reborrow(), Level::Low); // set the pin low
+        let out_pin = common.make_pio_pin(raw_pin);
+
+        let program = common.load_program(&prg.program);
+        cfg.use_program(&program, &[&out_pin]);
+
+        // cfg.set_out_pins(&[&out_pin]);
+        // mfg.set_set_pins(&[&out_pin]);
+        cfg.clock_divider = (U56F8!(125_000_000) / PIO_HZ).to_fixed();
+
+        cfg.shift_out.auto_fill = true;
+        cfg.shift_out.threshold = format.bits();
+        cfg.shift_out.direction = ShiftDirection::Left;
+
+        sm.set_pin_dirs(pio::Direction::Out, &[&out_pin]);
+        sm.set_config(&cfg);
+        sm.set_enable(true);
+
+        Self {
+            sm,
+            dma: dma.into_ref().map_into(),
+            buffer,
+            format,
+            ready_at: Instant::now(),
+        }
+    }
+}
+
+impl<PIO: Instance, const SM: usize> PixelSink for LedDriver<'_, PIO, SM> {
+    /// Returns as soon as the frame has started sending. Waits for the one
+    /// before it to finish and latch first, since they share a buffer.
+    async fn write(&mut self, pixels: &[Color]) {
+        Timer::at(self.ready_at).await;
+        // the DMA finishes before the PIO does, so it should be long done by
+        // now, but the buffer can't be touched until it is
+        while self.dma.regs().ctrl_trig().read().busy() {
+            yield_now().await;
+        }
+
+        let len = pixels.len();
+        for (word, color) in self.buffer.iter_mut().zip(pixels) {
+            *word = self.format.pack(*color);
+        }
+
+        let transfer = self
+            .sm
+            .tx()
+            .dma_push(self.dma.reborrow(), &self.buffer[..len]);
+        // dropping a transfer aborts it, and the whole point is to leave it
+        // running. `ready_at` stands in for awaiting it.
+        core::mem::forget(transfer);
+        self.ready_at = Instant::now() + self.format.send_time(len) + RESET_TIME;
+    }
+}
@@ -1,9 +1,12 @@
 #![no_std]
 
 mod color;
-pub use color::Color;
+pub use color::{Blend, Color, Hsl, Hsv, Oklab, Oklch, Palette, Stop};
 
+#[cfg(feature = "rp2040")]
 pub mod blue;
+pub mod config;
 pub mod led;
 pub mod lighting;
+#[cfg(feature = "rp2040")]
 pub mod panic;
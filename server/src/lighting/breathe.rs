@@ -0,0 +1,48 @@
+//! The whole segment slowly brightening and dimming, like breathing
+use crate::lighting::Animation;
+use crate::lighting::Easing;
+use crate::lighting::State;
+use crate::Color;
+
+/// How long a breath takes unless told otherwise, in tenths of a second
+const DEFAULT_PERIOD: u8 = 40;
+
+/// `base_color` pulsing between two levels. Starts at the bottom of a breath.
+#[derive(Debug)]
+pub struct Breathe {
+    /// Seconds per breath at speed 1.0
+    period: f32,
+    /// Levels at the bottom and top of a breath, 0-1
+    min: f32,
+    max: f32,
+    easing: Easing,
+    /// How far through the current breath, 0-1
+    phase: f32,
+}
+
+impl Breathe {
+    /// `period` is how long a breath takes in tenths of a second, with 0 for
+    /// the default of 4s. `min` and `max` are the levels it breathes
+    /// between, with a `max` of 0 for full.
+    pub fn new(period: u8, min: u8, max: u8, easing: Easing) -> Self {
+        let period = if period == 0 { DEFAULT_PERIOD } else { period };
+        let max = if max == 0 { 255 } else { max };
+
+        Self {
+            period: period as f32 / 10.,
+            min: min as f32 / 255.,
+            max: max as f32 / 255.,
+            easing,
+            phase: 0.,
+        }
+    }
+}
+
+impl Animation for Breathe {
+    fn animate(&mut self, delta: f32, state: &mut State, frame: &mut [Color]) {
+        self.phase = (self.phase + delta / self.period) % 1.;
+
+        let level = self.min + (self.max - self.min) * self.easing.pulse(self.phase);
+        frame.fill(state.base_color.dim(level));
+    }
+}
@@ -0,0 +1,109 @@
+//! Bright heads chasing along the strip, each trailing a fading tail
+use crate::lighting::Animation;
+use crate::lighting::State;
+use crate::Color;
+
+/// How far heads move at speed 1.0, in LEDs per second
+const LEDS_PER_SECOND: f32 = 30.;
+
+/// Most heads a comet can have, evenly spaced along the strip
+const MAX_HEADS: u8 = 16;
+
+/// How many LEDs a tail covers unless told otherwise
+const DEFAULT_TAIL: u8 = 8;
+
+/// Heads of `base_color` with exponentially fading tails. They either wrap
+/// round from one end of the segment to the other, or bounce back off the
+/// ends, with their tails folding round after them.
+#[derive(Debug)]
+pub struct Comet {
+    heads: u8,
+    /// How quickly the tail fades, per LED. It's down to 1/256 by the end.
+    decay: f32,
+    /// How many LEDs the tail covers
+    tail: f32,
+    reverse: bool,
+    bounce: bool,
+    /// Where the first head is, in LEDs along the path it travels, which
+    /// for bouncing is there and back again
+    position: f32,
+}
+
+impl Comet {
+    /// `heads` is 1-16, `tail` how many LEDs each tail covers, with 0 for the
+    /// default. `reverse` starts them the other way along the strip.
+    pub fn new(heads: u8, tail: u8, reverse: bool, bounce: bool) -> Self {
+        let tail = if tail == 0 { DEFAULT_TAIL } else { tail } as f32;
+
+        Self {
+            heads: heads.clamp(1, MAX_HEADS),
+            decay: libm::logf(256.) / tail,
+            tail,
+            reverse,
+            bounce,
+            position: 0.,
+        }
+    }
+
+    /// How far round the path is before it repeats, in LEDs
+    fn period(&self, len: usize) -> f32 {
+        let period = if self.bounce { 2 * (len - 1) } else { len };
+        period.max(1) as f32
+    }
+
+    /// How bright the LED that's `distance` behind a head is
+    fn level(&self, distance: f32) -> f32 {
+        if distance < 0. {
+            // just ahead of the head, so fade it in as the head gets closer
+            // instead of jumping a whole LED at a time
+            1. + distance
+        } else if distance < self.tail {
+            libm::expf(-self.decay * distance)
+        } else {
+            0.
+        }
+    }
+}
+
+impl Animation for Comet {
+    fn animate(&mut self, delta: f32, state: &mut State, frame: &mut [Color]) {
+        let len = frame.len();
+        if len == 0 {
+            return;
+        }
+
+        let period = self.period(len);
+        self.position = (self.position + delta * LEDS_PER_SECOND) % period;
+        let spacing = period / self.heads as f32;
+
+        for idx in 0..len {
+            // bouncing heads pass over each LED once each way
+            let there = idx as f32;
+            let phases = if self.bounce {
+                [there, period - there]
+            } else {
+                [there; 2]
+            };
+
+            // only the nearest head behind the LED and the nearest one just
+            // about to reach it can light it up
+            let mut behind = f32::INFINITY;
+            let mut ahead = -1.;
+            for head in 0..self.heads {
+                let head = self.position + head as f32 * spacing;
+                for phase in phases {
+                    let distance = (head - phase + period) % period;
+                    if distance > period - 1. {
+                        ahead = f32::max(ahead, distance - period);
+                    } else {
+                        behind = f32::min(behind, distance);
+                    }
+                }
+            }
+
+            let level = self.level(behind).max(self.level(ahead));
+            let idx = if self.reverse { len - 1 - idx } else { idx };
+            frame[idx] = state.base_color.dim(level);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Temporal dithering: turns 16 bit levels into 8 bit ones by carrying what
+//! got rounded off over to the next frame. A level between two 8 bit steps
+//! alternates between them, so over a few frames it averages out to the
+//! right brightness instead of getting stuck on the step below.
+use crate::led::MAX_LEDS;
+use crate::Color;
+
+pub struct Dither {
+    /// What's been rounded off so far, for every channel of every LED
+    error: [[u8; 3]; MAX_LEDS],
+}
+
+impl Dither {
+    pub const fn new() -> Self {
+        Self {
+            error: [[0; 3]; MAX_LEDS],
+        }
+    }
+
+    /// Quantize the 16 bit `[red, green, blue]` levels of LED `idx`
+    pub fn quantize(&mut self, idx: usize, levels: [u16; 3]) -> Color {
+        let error = &mut self.error[idx];
+        let [red, green, blue] = [0, 1, 2].map(|channel| {
+            let sum = levels[channel] as u32 + error[channel] as u32;
+            error[channel] = sum as u8;
+            (sum >> 8).min(255) as u8
+        });
+
+        Color::new(red, green, blue)
+    }
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,50 @@
+//! Curves for getting from 0 to 1 more gracefully than a straight line, and
+//! for pulsing back and forth along them
+use core::f32::consts::E;
+use core::f32::consts::PI;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// Slow at either end, like a sine wave
+    #[default]
+    Sine,
+    /// A straight line, so pulsing makes a triangle wave
+    Linear,
+    /// Lingers near 0 and swells quickly through the top. Pulsing makes the
+    /// sleep light breathing of old Macs.
+    Exponential,
+}
+
+impl Easing {
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Sine),
+            1 => Some(Self::Linear),
+            2 => Some(Self::Exponential),
+            _ => None,
+        }
+    }
+
+    /// How far along the curve `t` of the way through is, both 0-1. `t` is
+    /// clamped.
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Self::Sine => (1. - libm::cosf(PI * t)) / 2.,
+            Self::Linear => t,
+            Self::Exponential => (libm::expf(-libm::cosf(PI * t)) - 1. / E) / (E - 1. / E),
+        }
+    }
+
+    /// Up the curve and back down again over a cycle, where `phase` is how
+    /// far through it is. Wraps, so `phase` can keep counting up.
+    pub fn pulse(self, phase: f32) -> f32 {
+        let phase = phase - libm::floorf(phase);
+        let t = if phase < 0.5 {
+            phase * 2.
+        } else {
+            2. - phase * 2.
+        };
+        self.ease(t)
+    }
+}
@@ -0,0 +1,129 @@
+//! Flames rising along the strip, from a simulation of heat that sparks,
+//! rises and cools
+use crate::led::MAX_LEDS;
+use crate::led::MAX_STRIPS;
+use crate::lighting::Animation;
+use crate::lighting::State;
+use crate::Color;
+use crate::Palette;
+use crate::Stop;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_sync::mutex::MutexGuard;
+
+/// How many fires can burn at once, across every strip and segment. Enough
+/// for a fire on every strip to fade into another one.
+const MAX_FIRES: usize = MAX_STRIPS * 2;
+
+/// Heat is kept out of `Fire` itself so it doesn't bloat every `AnimationEnum`
+static HEAT: [Mutex<CriticalSectionRawMutex, [u8; MAX_LEDS]>; MAX_FIRES] =
+    [const { Mutex::new([0; MAX_LEDS]) }; MAX_FIRES];
+
+/// How many times a second the heat moves on at speed 1.0. The simulation
+/// only looks right in fixed steps, however fast frames come.
+const STEPS_PER_SECOND: f32 = 60.;
+
+/// Most steps one frame will catch up on, so a long stall doesn't hang the
+/// strip while it simulates everything it missed
+const MAX_STEPS: u32 = 4;
+
+/// Sparks only start this close to the bottom of the fire
+const SPARK_ZONE: usize = 7;
+
+const DEFAULT_COOLING: u8 = 55;
+const DEFAULT_SPARKING: u8 = 120;
+
+/// Black body colors from cold to hottest
+const PALETTE: Palette<5> = Palette::new([
+    Stop::new(0., Color::BLACK),
+    Stop::new(0.3, Color::RED),
+    Stop::new(0.6, Color::new(255, 128, 0)),
+    Stop::new(0.85, Color::YELLOW),
+    Stop::new(1., Color::WHITE),
+]);
+
+/// Heat sparks near the start of the segment, rises along it and cools as it
+/// goes. Drawn in fire colors rather than `base_color`.
+#[derive(Debug)]
+pub struct Fire {
+    heat: MutexGuard<'static, CriticalSectionRawMutex, [u8; MAX_LEDS]>,
+    /// How fast heat is lost, the higher the shorter the flames
+    cooling: u8,
+    /// The chance of a new spark each step, out of 255
+    sparking: u8,
+    /// Whether flames rise from both ends and meet in the middle
+    mirror: bool,
+    /// Steps of simulation owed, as a fraction
+    pending: f32,
+}
+
+impl Fire {
+    /// `cooling` and `sparking` are 1-255, with 0 for the defaults.
+    /// Returns `None` if every fire is already burning.
+    pub fn new(cooling: u8, sparking: u8, mirror: bool) -> Option<Self> {
+        let mut heat = HEAT.iter().find_map(|heat| heat.try_lock().ok())?;
+        *heat = [0; MAX_LEDS];
+
+        Some(Self {
+            heat,
+            cooling: if cooling == 0 {
+                DEFAULT_COOLING
+            } else {
+                cooling
+            },
+            sparking: if sparking == 0 {
+                DEFAULT_SPARKING
+            } else {
+                sparking
+            },
+            mirror,
+            pending: 0.,
+        })
+    }
+
+    /// Move the heat in the first `len` LEDs on by one step
+    fn step(&mut self, len: usize, state: &mut State) {
+        let rng = &mut state.rng;
+        let heat = &mut self.heat[..len];
+
+        // shorter fires cool faster per LED, so flames are the same
+        // proportion of the strip however long it is
+        let max_cooling = (self.cooling as usize * 10 / len + 2).min(255) as u8;
+        for cell in heat.iter_mut() {
+            *cell = cell.saturating_sub(rng.u8(0..=max_cooling));
+        }
+
+        // heat rises, and spreads out a little as it does
+        for idx in (2..len).rev() {
+            let below = heat[idx - 1] as u16 + 2 * heat[idx - 2] as u16;
+            heat[idx] = (below / 3) as u8;
+        }
+
+        if rng.u8(..) < self.sparking {
+            let idx = rng.usize(0..len.min(SPARK_ZONE));
+            heat[idx] = heat[idx].saturating_add(rng.u8(160..=255));
+        }
+    }
+}
+
+impl Animation for Fire {
+    fn animate(&mut self, delta: f32, state: &mut State, frame: &mut [Color]) {
+        let len = frame.len();
+        if len == 0 {
+            return;
+        }
+        // mirrored fires are two half as tall, one from each end
+        let height = if self.mirror { len.div_ceil(2) } else { len };
+
+        self.pending = (self.pending + delta * STEPS_PER_SECOND).min(MAX_STEPS as f32);
+        while self.pending >= 1. {
+            self.step(height, state);
+            self.pending -= 1.;
+        }
+
+        for (idx, pixel) in frame.iter_mut().enumerate() {
+            let idx = if idx < height { idx } else { len - 1 - idx };
+            *pixel = PALETTE.sample(self.heat[idx] as f32 / 255.);
+        }
+    }
+}
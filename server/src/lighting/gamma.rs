@@ -0,0 +1,74 @@
+//! Maps how bright a channel should look to how hard the LED has to be driven.
+//! LEDs are linear but eyes aren't, so without this half brightness looks
+//! nearly as bright as full and the bottom few steps jump visibly.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Curve {
+    /// CIE 1976 lightness, the perceptual default
+    #[default]
+    Cie,
+    /// A plain power curve. 1.0 leaves values alone.
+    Gamma(f32),
+}
+
+impl Curve {
+    /// `x` and the result are both between 0 and 1
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            Self::Cie => {
+                let lightness = x * 100.;
+                if lightness <= 8. {
+                    lightness / 903.3
+                } else {
+                    let y = (lightness + 16.) / 116.;
+                    y * y * y
+                }
+            }
+            Self::Gamma(gamma) => libm::powf(x, gamma),
+        }
+    }
+
+    /// `0` for `Cie`, anything else is a gamma in tenths, e.g. `22` for 2.2
+    pub fn from_byte(byte: u8) -> Self {
+        match byte {
+            0 => Self::Cie,
+            gamma => Self::Gamma(gamma as f32 / 10.),
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Self::Cie => 0,
+            Self::Gamma(gamma) => libm::roundf(gamma * 10.).clamp(1., 255.) as u8,
+        }
+    }
+}
+
+/// A `Curve` baked into a lookup table, so it's cheap enough to run on every
+/// pixel. Works on 16 bit levels, so the dark end, where the curve is flattest,
+/// doesn't get rounded away before dithering.
+pub struct GammaTable {
+    /// The curve at every 256th level, plus one past the end to interpolate towards
+    table: [u16; 257],
+}
+
+impl GammaTable {
+    pub fn new(curve: Curve) -> Self {
+        let mut table = [0; 257];
+        for (idx, out) in table.iter_mut().enumerate() {
+            let x = (idx as f32 / 256.).min(1.);
+            *out = (curve.apply(x) * 65535. + 0.5) as u16;
+        }
+
+        Self { table }
+    }
+
+    /// `level` and the result are both between 0 and `u16::MAX`
+    pub fn correct(&self, level: u16) -> u16 {
+        let idx = (level >> 8) as usize;
+        let frac = (level & 0xff) as i32;
+        let (low, high) = (self.table[idx] as i32, self.table[idx + 1] as i32);
+
+        (low + (high - low) * frac / 256) as u16
+    }
+}
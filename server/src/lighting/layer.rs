@@ -0,0 +1,112 @@
+//! One animation in a segment's stack, drawn over the layers below it
+use crate::lighting::Animation;
+use crate::lighting::AnimationEnum;
+use crate::lighting::Fade;
+use crate::lighting::State;
+use crate::Blend;
+use crate::Color;
+
+/// How many layers a segment can stack
+pub const MAX_LAYERS: usize = 4;
+
+pub struct Layer {
+    animation: Option<AnimationEnum>,
+    /// The animation being faded out after switching to another one
+    outgoing: Option<Outgoing>,
+    pub blend: Blend,
+    /// How much the layer shows over the ones below, 0-1
+    pub opacity: Fade<f32>,
+}
+
+struct Outgoing {
+    animation: Option<AnimationEnum>,
+    /// How much of the incoming animation shows, 0-1
+    mix: Fade<f32>,
+}
+
+impl Layer {
+    /// A layer showing a solid `base_color`
+    pub const fn new(blend: Blend, opacity: Fade<f32>) -> Self {
+        Self {
+            animation: None,
+            outgoing: None,
+            blend,
+            opacity,
+        }
+    }
+
+    /// Switch to the animation `new` makes, fading the current one out over
+    /// `transition` seconds
+    pub fn switch(&mut self, transition: f32, new: impl FnOnce() -> Option<AnimationEnum>) {
+        // animations have to let go of their buffers before new ones can
+        // have them, so only one gets faded out at a time
+        drop(self.outgoing.take());
+        let outgoing = self.animation.take();
+        if transition > 0. {
+            let mut mix = Fade::new(0.);
+            mix.set(1., transition);
+            self.outgoing = Some(Outgoing {
+                animation: outgoing,
+                mix,
+            });
+        } else {
+            drop(outgoing);
+        }
+
+        self.animation = new();
+    }
+
+    /// Draw the layer into `frame`, `delta` real seconds or `animation_delta`
+    /// seconds of animation time after the last one. `scratch` has to be at
+    /// least as long as `frame`, it's where an outgoing animation gets drawn
+    /// to be faded out.
+    pub fn draw(
+        &mut self,
+        delta: f32,
+        animation_delta: f32,
+        state: &mut State,
+        frame: &mut [Color],
+        scratch: &mut [Color],
+    ) {
+        self.opacity.tick(delta);
+        draw(&mut self.animation, animation_delta, state, frame);
+
+        let Some(outgoing) = &mut self.outgoing else {
+            return;
+        };
+
+        outgoing.mix.tick(delta);
+        if outgoing.mix.is_done() {
+            self.outgoing = None;
+            return;
+        }
+
+        let scratch = &mut scratch[..frame.len()];
+        draw(&mut outgoing.animation, animation_delta, state, scratch);
+        let mix = outgoing.mix.value();
+        for (new, old) in frame.iter_mut().zip(scratch) {
+            *new = old.lerp(*new, mix);
+        }
+    }
+
+    /// Blend `layer`, as drawn by `draw`, over `frame`
+    pub fn composite(&self, frame: &mut [Color], layer: &[Color]) {
+        let opacity = self.opacity.value();
+        for (below, above) in frame.iter_mut().zip(layer) {
+            let blended = self.blend.apply(*below, *above);
+            *below = if opacity >= 1. {
+                blended
+            } else {
+                below.lerp(blended, opacity)
+            };
+        }
+    }
+}
+
+/// No animation means a solid `base_color`
+fn draw(animation: &mut Option<AnimationEnum>, delta: f32, state: &mut State, frame: &mut [Color]) {
+    match animation {
+        Some(a) => a.animate(delta, state, frame),
+        None => frame.fill(state.base_color),
+    }
+}
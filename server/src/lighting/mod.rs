@@ -1,60 +1,148 @@
 //! Lighting state and task
+mod breathe;
+mod comet;
+mod dither;
+mod easing;
+mod fire;
+mod gamma;
+mod layer;
+mod output;
+mod plasma;
+mod power;
+mod rainbow;
+mod scheduler;
+mod segment;
+mod transition;
 mod twinkle;
 
-use embassy_rp::peripherals::PIO1;
+use core::sync::atomic::AtomicU16;
+use core::sync::atomic::Ordering;
+
+use breathe::Breathe;
+use comet::Comet;
+use embassy_futures::select::select;
+use embassy_futures::select::Either;
+use embassy_time::Duration;
 use embassy_time::Instant;
 use embassy_time::Timer;
 use enum_dispatch::enum_dispatch;
+use fastrand::Rng;
+use fire::Fire;
 use log::info;
+use log::warn;
+use plasma::Plasma;
+use rainbow::Rainbow;
+use rainbow::RainbowMode;
 use twinkle::Twinkle;
 
 use embassy_sync::{blocking_mutex::raw::RawMutex, channel::Receiver};
 
-use crate::led::LedDriver;
-use crate::led::NUM_LEDS;
+use crate::led::PixelSink;
+use crate::led::DEFAULT_LEDS;
+use crate::led::MAX_LEDS;
+use crate::Blend;
 use crate::Color;
+pub use dither::Dither;
+pub use easing::Easing;
+pub use gamma::Curve;
+pub use gamma::GammaTable;
+pub use layer::Layer;
+pub use layer::MAX_LAYERS;
+pub use output::Output;
+pub use power::PowerLimit;
+pub use power::PowerModel;
+pub use power::DEFAULT_BUDGET_MA;
+pub use scheduler::FrameStats;
+pub use scheduler::Scheduler;
+pub use scheduler::DEFAULT_FPS;
+pub use scheduler::MAX_FPS;
+pub use segment::Segment;
+pub use segment::MAX_SEGMENTS;
+pub use transition::Fade;
+pub use transition::Lerp;
+pub use transition::DEFAULT_TRANSITION;
 
-type Driver = LedDriver<'static, PIO1, 0>;
+/// One color per LED, in strip order. Only as many as the strip is long are used.
+pub type Frame = [Color; MAX_LEDS];
 
 #[enum_dispatch(AnimationEnum)]
-trait Animation {
-    async fn animate(&mut self, delta: f32, state: &mut State);
+pub trait Animation {
+    /// Draw the next frame into `frame`, which is `state.length` long.
+    /// `delta` is how many seconds of animation time have passed since the
+    /// last frame: real seconds times the segment's speed, so at speed 1.0
+    /// animations run at their natural pace and at 2.0 twice as fast.
+    ///
+    /// Brightness and skip are applied afterwards by the output stage, so
+    /// animations shouldn't use them.
+    fn animate(&mut self, delta: f32, state: &mut State, frame: &mut [Color]);
 }
 
-struct State {
-    driver: Driver,
-    base_color: Color,
-    brightness: f32,
-    skip: u8,
+pub struct State {
+    pub base_color: Color,
+    pub brightness: f32,
+    pub skip: u8,
+    /// How many LEDs the segment being drawn covers
+    pub length: usize,
+    /// The only source of randomness animations should use, so that the same
+    /// seed always renders the same frames
+    pub rng: Rng,
 }
 
 impl State {
-    fn new(driver: Driver) -> Self {
+    pub fn new(seed: u64) -> Self {
         Self {
-            driver,
             base_color: Color::WHITE,
             brightness: 1.0,
             skip: 0,
+            length: DEFAULT_LEDS,
+            rng: Rng::with_seed(seed),
         }
     }
 }
 
-#[derive(Debug)]
 #[enum_dispatch]
+#[derive(Debug)]
 pub enum AnimationEnum {
     Twinkle,
+    Plasma,
+    Rainbow,
+    Comet,
+    Fire,
+    Breathe,
 }
 
 impl AnimationEnum {
-    pub fn from_bytes(bytes: [u8; 16]) -> Option<Self> {
+    pub fn from_bytes(bytes: [u8; 16], state: &mut State) -> Option<Self> {
         info!("AnimationEnum::from_bytes({bytes:?})");
         match bytes[0] {
-            1 => Some(Twinkle::new(bytes[1]).into()),
+            1 => Twinkle::new(bytes[1], state)
+                .map(Into::into)
+                .or_else(|| exhausted("twinkle")),
+            2 => Some(Plasma::new(bytes[1], bytes[2], bytes[3], state).into()),
+            3 => {
+                let mode = RainbowMode::from_byte(bytes[1])?;
+                Some(Rainbow::new(mode, bytes[2], bytes[3] != 0, bytes[4]).into())
+            }
+            4 => Some(Comet::new(bytes[1], bytes[2], bytes[3] != 0, bytes[4] != 0).into()),
+            5 => Fire::new(bytes[1], bytes[2], bytes[3] != 0)
+                .map(Into::into)
+                .or_else(|| exhausted("fire")),
+            6 => {
+                let easing = Easing::from_byte(bytes[4])?;
+                Some(Breathe::new(bytes[1], bytes[2], bytes[3], easing).into())
+            }
             _ => None,
         }
     }
 }
 
+/// For animations that keep their buffers in a fixed pool, when every one is
+/// taken. The layer falls back to solid `base_color`.
+fn exhausted(animation: &str) -> Option<AnimationEnum> {
+    warn!("[lighting] every {animation} is in use, showing a solid color instead");
+    None
+}
+
 #[derive(Debug)]
 pub enum Message {
     /// This message does nothing
@@ -67,65 +155,205 @@ pub enum Message {
     SetSkip(u8),
     /// Use a color animation
     UseAnimation([u8; 16]),
-    /// Set speed of animation
+    /// Set how fast animations run, 1.0 being their natural pace
     SetAnimationSpeed(f32),
+    /// Set how many LEDs are on the strip, clamped to 1-`MAX_LEDS`
+    SetLength(u16),
+    /// Pick which segment the color, brightness, skip, animation and speed
+    /// messages apply to
+    SelectSegment(u8),
+    /// Make the selected segment cover LEDs `start..end`, creating it if needed.
+    /// An empty range removes the segment.
+    SetSegment(u16, u16),
+    /// Set the brightness curve applied to everything sent to the strip
+    SetCurve(Curve),
+    /// Set how many milliamps the strip may draw, `0` for no limit
+    SetPowerBudget(u16),
+    /// Set the lights to a white of this many kelvin
+    SetTemperature(u16),
+    /// Scale each channel of everything sent to the strip, `Color::WHITE`
+    /// leaves it alone. Makes up for strips whose white isn't quite white.
+    SetWhiteBalance(Color),
+    /// Set how many milliseconds color, brightness and animation changes
+    /// take to fade in
+    SetTransition(u16),
+    /// Pick which layer of the selected segment `UseAnimation` and
+    /// `SetLayer` apply to
+    SelectLayer(u8),
+    /// Make the selected layer draw over the ones below it with this blend
+    /// mode and opacity out of 255, creating it if needed. Zero opacity
+    /// removes the layer.
+    SetLayer(Blend, u8),
+    /// Set how many frames a second the strip gets, clamped to 1-`MAX_FPS`
+    SetFrameRate(u8),
 }
 
-pub async fn run<M: RawMutex, const N: usize>(
-    led_driver: Driver,
-    recv: Receiver<'_, M, Message, N>,
-) -> ! {
-    let mut state = State::new(led_driver);
+/// How often frame time stats get logged
+const STATS_INTERVAL: Duration = Duration::from_secs(10);
 
-    let mut animation_speed = 1.0;
-    let mut current_animation = None;
+/// Everything needed to light up one strip: its segments, their animations
+/// and where the frames go.
+pub struct Lighting<S: PixelSink> {
+    output: Output<S>,
+    frame: Frame,
+    /// Where segments draw each layer before blending it in
+    layer_frame: Frame,
+    /// Where segments draw animations they're fading out
+    outgoing_frame: Frame,
+    /// How many LEDs the strip has, at most `MAX_LEDS`
+    length: usize,
+    segments: [Option<Segment>; MAX_SEGMENTS],
+    selected: usize,
+    seed: u64,
+    fps: u8,
+}
 
-    let mut previous = Instant::now();
-    loop {
-        if let Ok(message) = recv.try_receive() {
-            info!("[lighting] handling message {message:?}");
-            match message {
-                Message::Noop => {}
-                Message::SetColor(c) => {
-                    state.base_color = c;
-                }
-                Message::SetBrightness(b) => {
-                    state.brightness = (b as f32) / 255.;
-                }
-                Message::SetSkip(s) => {
-                    state.skip = s;
-                }
-                Message::UseAnimation(bytes) => {
-                    drop(current_animation);
-                    current_animation = AnimationEnum::from_bytes(bytes);
-                }
-                Message::SetAnimationSpeed(speed) => {
-                    animation_speed = speed;
-                }
-            }
+impl<S: PixelSink> Lighting<S> {
+    /// Starts out with a single segment covering the whole strip
+    pub fn new(sink: S, seed: u64) -> Self {
+        let mut segments = [const { None }; MAX_SEGMENTS];
+        segments[0] = Some(Segment::new(0, MAX_LEDS, seed));
+
+        Self {
+            output: Output::new(sink),
+            frame: [Color::BLACK; MAX_LEDS],
+            layer_frame: [Color::BLACK; MAX_LEDS],
+            outgoing_frame: [Color::BLACK; MAX_LEDS],
+            length: DEFAULT_LEDS,
+            segments,
+            selected: 0,
+            seed,
+            fps: DEFAULT_FPS,
         }
+    }
+
+    pub fn sink(&self) -> &S {
+        self.output.sink()
+    }
+
+    /// How many frames a second `run` should render
+    pub fn fps(&self) -> u8 {
+        self.fps
+    }
+
+    /// Estimated draw of the last rendered frame, in milliamps
+    pub fn draw(&self) -> u32 {
+        self.output.draw()
+    }
 
-        match &mut current_animation {
-            Some(a) => {
-                let delta = previous.elapsed().as_micros() as f32 / 1_000_000.0;
-                a.animate(delta * 40. * animation_speed, &mut state).await;
+    pub fn handle(&mut self, message: Message) {
+        info!("[lighting] handling message {message:?}");
+        match message {
+            Message::Noop => {}
+            Message::SetLength(length) => {
+                self.length = (length as usize).clamp(1, MAX_LEDS);
+            }
+            Message::SetCurve(curve) => {
+                self.output.set_curve(curve);
+            }
+            Message::SetPowerBudget(budget) => {
+                self.output.set_power_budget(budget);
+            }
+            Message::SetFrameRate(fps) => {
+                self.fps = fps.clamp(1, MAX_FPS);
+            }
+            Message::SetWhiteBalance(balance) => {
+                self.output.set_white_balance(balance);
+            }
+            Message::SelectSegment(id) if (id as usize) < MAX_SEGMENTS => {
+                self.selected = id as usize;
+            }
+            Message::SelectSegment(id) => {
+                info!("[lighting] no segment {id}");
             }
-            None => {
-                let color = state.base_color.dim(state.brightness);
-                let mut n = state.skip;
-                for _ in 0..NUM_LEDS {
-                    if n == 0 {
-                        state.driver.send_color(color).await;
-                        n = state.skip;
-                    } else {
-                        state.driver.send_color(Color::BLACK).await;
-                        n -= 1;
+            Message::SetSegment(start, end) if start >= end => {
+                self.segments[self.selected] = None;
+            }
+            Message::SetSegment(start, end) => {
+                let (start, end) = (start as usize, end as usize);
+                match &mut self.segments[self.selected] {
+                    Some(segment) => {
+                        segment.start = start;
+                        segment.end = end;
+                    }
+                    None => {
+                        // every segment gets its own seed, so they don't all twinkle in sync
+                        let seed = self.seed.wrapping_add(self.selected as u64);
+                        self.segments[self.selected] = Some(Segment::new(start, end, seed));
                     }
                 }
             }
+            message => match &mut self.segments[self.selected] {
+                Some(segment) => segment.handle(message, self.length),
+                None => info!("[lighting] segment {} doesn't exist", self.selected),
+            },
+        }
+    }
+
+    /// Render the next frame, `delta` seconds after the previous one, and
+    /// return it as it would be sent to the strip
+    pub fn render(&mut self, delta: f32) -> &[Color] {
+        // LEDs outside every segment stay dark. Later segments draw over
+        // earlier ones where they overlap.
+        self.frame[..self.length].fill(Color::BLACK);
+        for segment in self.segments.iter_mut().flatten() {
+            let range = segment.range(self.length);
+            segment.render(
+                delta,
+                &mut self.frame[range],
+                &mut self.layer_frame,
+                &mut self.outgoing_frame,
+            );
         }
 
-        Timer::after_micros(500).await;
-        previous = Instant::now();
+        self.output
+            .process(&self.frame[..self.length], &self.segments)
+    }
+
+    /// Render the next frame and send it to the strip
+    pub async fn show(&mut self, delta: f32) {
+        self.render(delta);
+        self.output.send().await;
+    }
+}
+
+/// Show frames on `lighting` forever, handling messages from `recv` between
+/// them. `lighting` is borrowed rather than built here since it's far too big
+/// for a small stack like core1's.
+pub async fn run<S: PixelSink, M: RawMutex, const N: usize>(
+    lighting: &mut Lighting<S>,
+    recv: Receiver<'_, M, Message, N>,
+    draw: &AtomicU16,
+) -> ! {
+    let mut scheduler = Scheduler::new(lighting.fps());
+    let mut last_stats = Instant::now();
+
+    loop {
+        let delta = scheduler.begin(Instant::now());
+        lighting.show(delta).await;
+        draw.store(
+            lighting.draw().min(u16::MAX as u32) as u16,
+            Ordering::Relaxed,
+        );
+
+        // messages get handled while waiting for the next frame, so they
+        // neither delay it nor wait for it
+        let next = scheduler.end(Instant::now());
+        while let Either::First(message) = select(recv.receive(), Timer::at(next)).await {
+            lighting.handle(message);
+        }
+        scheduler.set_fps(lighting.fps());
+
+        if last_stats.elapsed() >= STATS_INTERVAL {
+            let stats = scheduler.take_stats();
+            info!(
+                "[lighting] {} frames, {} late, {}us average, {}us worst",
+                stats.frames,
+                stats.late,
+                stats.average().as_micros(),
+                stats.worst.as_micros()
+            );
+            last_stats = Instant::now();
+        }
     }
 }
@@ -0,0 +1,126 @@
+//! Output stage: turns a rendered frame into what actually goes down the wire
+use crate::led::PixelSink;
+use crate::led::MAX_LEDS;
+use crate::lighting::Curve;
+use crate::lighting::Dither;
+use crate::lighting::Frame;
+use crate::lighting::GammaTable;
+use crate::lighting::PowerLimit;
+use crate::lighting::Segment;
+use crate::lighting::DEFAULT_BUDGET_MA;
+use crate::Color;
+
+pub struct Output<S: PixelSink> {
+    sink: S,
+    /// 16 bit `[red, green, blue]` levels, before the power limit and dithering
+    levels: [[u16; 3]; MAX_LEDS],
+    buffer: Frame,
+    /// How many LEDs the last processed frame covered
+    len: usize,
+    /// How many pixels `send` should write. Longer than `len` for one frame
+    /// after the strip gets shorter, so the LEDs past the new end get cleared.
+    send_len: usize,
+    gamma: GammaTable,
+    /// Per channel gain applied after the gamma curve, out of 255
+    white_balance: Color,
+    dither: Dither,
+    power: PowerLimit,
+    /// Estimated draw of the last processed frame, in milliamps
+    draw: u32,
+}
+
+impl<S: PixelSink> Output<S> {
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            levels: [[0; 3]; MAX_LEDS],
+            buffer: [Color::BLACK; MAX_LEDS],
+            len: 0,
+            send_len: 0,
+            gamma: GammaTable::new(Curve::default()),
+            white_balance: Color::WHITE,
+            dither: Dither::new(),
+            power: PowerLimit::new(DEFAULT_BUDGET_MA),
+            draw: 0,
+        }
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    pub fn set_curve(&mut self, curve: Curve) {
+        self.gamma = GammaTable::new(curve);
+    }
+
+    pub fn set_white_balance(&mut self, balance: Color) {
+        self.white_balance = balance;
+    }
+
+    /// In milliamps, `0` turns the limit off
+    pub fn set_power_budget(&mut self, budget: u16) {
+        self.power.budget = budget;
+    }
+
+    /// Estimated draw of the last processed frame, in milliamps
+    pub fn draw(&self) -> u32 {
+        self.draw
+    }
+
+    /// Apply each segment's brightness and skip mask to its part of `frame`,
+    /// then the gamma curve, white balance, the power limit and dithering,
+    /// without sending it anywhere.
+    ///
+    /// Brightness and gamma are worked out in 16 bits, so a dim segment
+    /// still fades smoothly instead of stepping through a few 8 bit levels.
+    pub fn process(&mut self, frame: &[Color], segments: &[Option<Segment>]) -> &[Color] {
+        let len = frame.len();
+        self.send_len = self.len.max(len);
+        self.levels[..len].fill([0; 3]);
+        self.len = len;
+
+        let balance = [
+            self.white_balance.red(),
+            self.white_balance.green(),
+            self.white_balance.blue(),
+        ];
+
+        for segment in segments.iter().flatten() {
+            let range = segment.range(len);
+            let state = &segment.state;
+
+            // brightness as a 16.16 fixed point multiplier
+            let scale = (state.brightness.clamp(0., 1.) * 65536.) as u32;
+
+            let mut n = state.skip;
+            for (levels, color) in self.levels[range.clone()].iter_mut().zip(&frame[range]) {
+                if n == 0 {
+                    let channels = [color.red(), color.green(), color.blue()];
+                    for ((out, channel), gain) in levels.iter_mut().zip(channels).zip(balance) {
+                        let level = (channel as u32 * 257 * scale) >> 16;
+                        let level = self.gamma.correct(level as u16) as u32 * gain as u32 / 255;
+                        *out = level as u16;
+                    }
+                    n = state.skip;
+                } else {
+                    *levels = [0; 3];
+                    n -= 1;
+                }
+            }
+        }
+
+        self.draw = self.power.limit(&mut self.levels[..len]);
+
+        for (idx, levels) in self.levels[..len].iter().enumerate() {
+            self.buffer[idx] = self.dither.quantize(idx, *levels);
+        }
+        self.buffer[len..self.send_len].fill(Color::BLACK);
+
+        &self.buffer[..len]
+    }
+
+    /// Send the last processed frame to the strip
+    pub async fn send(&mut self) {
+        self.sink.write(&self.buffer[..self.send_len]).await;
+    }
+}
@@ -0,0 +1,78 @@
+//! Blobs of light that drift and morph along the strip, drawn from noise
+use noise_functions::OpenSimplex2;
+use noise_functions::Sample2;
+
+use crate::lighting::Animation;
+use crate::lighting::State;
+use crate::Color;
+use crate::Hsv;
+
+/// Every octave costs another noise sample per LED, and past this the extra
+/// detail is finer than the LEDs anyway
+const MAX_OCTAVES: u8 = 4;
+
+/// How many LEDs a blob spans unless told otherwise
+const DEFAULT_SCALE: u8 = 16;
+
+/// An f32 can't count frame-sized steps of time for long before they get
+/// rounded away, so time wraps back round after this many seconds. That makes
+/// the pattern jump every 17 minutes at speed 1.0, and proportionally more
+/// often when it's sped up.
+const WRAP: f32 = 1024.;
+
+/// 2D noise over (LED, time) that dims `base_color` and turns its hue. At
+/// speed 1.0 the pattern changes about once a second.
+#[derive(Debug)]
+pub struct Plasma {
+    /// Noise units per LED
+    frequency: f32,
+    octaves: u32,
+    /// How far the hue can turn either side of `base_color`, in degrees
+    hue_spread: f32,
+    seed: i32,
+    /// Seconds of animation time since the animation started
+    time: f32,
+}
+
+impl Plasma {
+    /// `scale` is how many LEDs a blob spans, 0 for the default.
+    /// `octaves` is how many layers of finer detail there are, 1-4.
+    /// `hue_spread` is how far the hue wanders from `base_color`, from 0 for
+    /// not at all to 255 for all the way round.
+    pub fn new(scale: u8, octaves: u8, hue_spread: u8, state: &mut State) -> Self {
+        let scale = if scale == 0 { DEFAULT_SCALE } else { scale };
+
+        Self {
+            frequency: 1. / scale as f32,
+            octaves: octaves.clamp(1, MAX_OCTAVES) as u32,
+            hue_spread: hue_spread as f32 * 180. / 255.,
+            seed: state.rng.i32(..),
+            time: 0.,
+        }
+    }
+}
+
+impl Animation for Plasma {
+    fn animate(&mut self, delta: f32, state: &mut State, frame: &mut [Color]) {
+        self.time = (self.time + delta) % WRAP;
+
+        let noise = OpenSimplex2.fbm(self.octaves, 0.5, 2.);
+        // separate seeds so bright spots aren't always the same hue
+        let brightness = noise.seed(self.seed);
+        let hue = noise.seed(self.seed.wrapping_add(1));
+        let base = Hsv::from(state.base_color);
+
+        for (idx, pixel) in frame.iter_mut().enumerate() {
+            let point = [idx as f32 * self.frequency, self.time];
+            // the noise is roughly -1 to 1
+            let level = (brightness.sample2(point) * 0.5 + 0.5).clamp(0., 1.);
+            let color = if self.hue_spread > 0. {
+                Color::from(base.rotate(hue.sample2(point) * self.hue_spread))
+            } else {
+                state.base_color
+            };
+
+            *pixel = color.dim(level);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Keeps a strip from drawing more current than the supply can deliver.
+//! A full white strip of 90 LEDs wants several amps, and a USB port browning
+//! out resets the Pico, radio and all.
+
+/// Budget strips start out with. Leaves some of a 500 mA USB port for the
+/// Pico W itself.
+pub const DEFAULT_BUDGET_MA: u16 = 400;
+
+/// How much current an LED draws
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PowerModel {
+    /// Milliamps each channel draws when fully on
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+    /// Milliamps each LED draws even when it's dark
+    pub idle: u32,
+}
+
+impl PowerModel {
+    /// Typical figures for a 5V WS2812B
+    pub const WS2812: Self = Self {
+        red: 16,
+        green: 11,
+        blue: 15,
+        idle: 1,
+    };
+
+    /// Estimated draw of LEDs showing the 16 bit `levels`, in microamps
+    pub fn estimate(&self, levels: &[[u16; 3]]) -> u32 {
+        let active: u64 = levels
+            .iter()
+            .map(|&[red, green, blue]| {
+                red as u64 * self.red as u64
+                    + green as u64 * self.green as u64
+                    + blue as u64 * self.blue as u64
+            })
+            .sum();
+
+        let active = active * 1000 / u16::MAX as u64;
+        let idle = levels.len() as u64 * self.idle as u64 * 1000;
+
+        (active + idle).min(u32::MAX as u64) as u32
+    }
+}
+
+pub struct PowerLimit {
+    pub model: PowerModel,
+    /// In milliamps, or `0` for no limit
+    pub budget: u16,
+}
+
+impl PowerLimit {
+    pub const fn new(budget: u16) -> Self {
+        Self {
+            model: PowerModel::WS2812,
+            budget,
+        }
+    }
+
+    /// Scale `levels` down so they fit in the budget, if they don't already.
+    /// Returns the estimated draw afterwards, in milliamps.
+    pub fn limit(&self, levels: &mut [[u16; 3]]) -> u32 {
+        let draw = self.model.estimate(levels);
+        let budget = self.budget as u32 * 1000;
+
+        if self.budget == 0 || draw <= budget {
+            return draw.div_ceil(1000);
+        }
+
+        // LEDs draw their idle current no matter what, so only the rest scales.
+        // 16.16 fixed point, rounded down so the result stays under budget.
+        let idle = levels.len() as u32 * self.model.idle * 1000;
+        if draw <= idle {
+            // already dark, and there's nothing left to scale
+            levels.fill([0; 3]);
+            return draw.div_ceil(1000);
+        }
+        let scale = budget.saturating_sub(idle) as u64 * 65536 / (draw - idle) as u64;
+        for channel in levels.iter_mut().flatten() {
+            *channel = ((*channel as u64 * scale) >> 16) as u16;
+        }
+
+        self.model.estimate(levels).div_ceil(1000)
+    }
+}
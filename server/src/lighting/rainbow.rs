@@ -0,0 +1,93 @@
+//! Hue sweeps across the strip, over time, or both
+use crate::lighting::Animation;
+use crate::lighting::State;
+use crate::Color;
+use crate::Hsv;
+
+/// How fast `Scroll` and `Cycle` turn the hue at speed 1.0, in degrees per
+/// second, so once round the wheel every ten seconds
+const DEGREES_PER_SECOND: f32 = 36.;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RainbowMode {
+    /// A rainbow across the strip that stays put
+    Static,
+    /// A rainbow across the strip that moves along it
+    Scroll,
+    /// The whole strip one color, going round the wheel
+    Cycle,
+}
+
+impl RainbowMode {
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Static),
+            1 => Some(Self::Scroll),
+            2 => Some(Self::Cycle),
+            _ => None,
+        }
+    }
+}
+
+/// Starts from the hue of `base_color` and keeps its value, so a dimmer
+/// base color makes a dimmer rainbow
+#[derive(Debug)]
+pub struct Rainbow {
+    mode: RainbowMode,
+    /// How far round the wheel the hue gets from one end of the segment to
+    /// the other, in degrees
+    span: f32,
+    /// 1.0 or -1.0, which way round the wheel to go along the strip and
+    /// over time
+    direction: f32,
+    saturation: f32,
+    /// How far the hue has turned since the animation started, in degrees
+    offset: f32,
+}
+
+impl Rainbow {
+    /// `span` is how much of the wheel fits across the segment, with 0 for
+    /// all of it. `reverse` runs it the other way. `saturation` is 1-255,
+    /// with 0 for fully saturated since a rainbow of white isn't much use.
+    pub fn new(mode: RainbowMode, span: u8, reverse: bool, saturation: u8) -> Self {
+        let span = if span == 0 {
+            360.
+        } else {
+            span as f32 * 360. / 255.
+        };
+        let saturation = if saturation == 0 { 255 } else { saturation };
+
+        Self {
+            mode,
+            span,
+            direction: if reverse { -1. } else { 1. },
+            saturation: saturation as f32 / 255.,
+            offset: 0.,
+        }
+    }
+}
+
+impl Animation for Rainbow {
+    fn animate(&mut self, delta: f32, state: &mut State, frame: &mut [Color]) {
+        if self.mode != RainbowMode::Static {
+            // backwards, so that scrolling colors move away from the start
+            // of the segment rather than towards it
+            let turn = delta * DEGREES_PER_SECOND * self.direction;
+            self.offset = (self.offset - turn) % 360.;
+        }
+
+        let base = Hsv::from(state.base_color);
+        let start = Hsv::new(base.hue, self.saturation, base.value).rotate(self.offset);
+
+        if self.mode == RainbowMode::Cycle {
+            frame.fill(start.into());
+            return;
+        }
+
+        // spread over the whole segment so a full wheel wraps round seamlessly
+        let step = self.span * self.direction / frame.len().max(1) as f32;
+        for (idx, pixel) in frame.iter_mut().enumerate() {
+            *pixel = start.rotate(idx as f32 * step).into();
+        }
+    }
+}
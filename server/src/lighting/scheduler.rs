@@ -0,0 +1,120 @@
+//! Paces frames at a steady rate, whatever the strip length or animation,
+//! and keeps track of how long they take
+use embassy_time::Duration;
+use embassy_time::Instant;
+
+/// Frame rate strips start out at
+pub const DEFAULT_FPS: u8 = 60;
+/// A full 300 LED RGBW strip, the slowest kind, can take 13.2ms to send plus
+/// 0.28ms to latch, so faster than this can't keep up
+pub const MAX_FPS: u8 = 74;
+
+pub struct Scheduler {
+    interval: Duration,
+    /// When the current frame started
+    start: Option<Instant>,
+    stats: FrameStats,
+}
+
+impl Scheduler {
+    pub fn new(fps: u8) -> Self {
+        let mut scheduler = Self {
+            interval: Duration::from_secs(1),
+            start: None,
+            stats: FrameStats::new(),
+        };
+        scheduler.set_fps(fps);
+        scheduler
+    }
+
+    /// Clamped to 1-`MAX_FPS`
+    pub fn set_fps(&mut self, fps: u8) {
+        self.interval = Duration::from_hz(fps.clamp(1, MAX_FPS) as u64);
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Start a frame at `now`. Returns how many seconds it's been since the
+    /// last one started, which is one interval unless frames ran late.
+    pub fn begin(&mut self, now: Instant) -> f32 {
+        let delta = match self.start {
+            Some(start) => now.saturating_duration_since(start),
+            None => Duration::from_ticks(0),
+        };
+        self.start = Some(now);
+
+        delta.as_micros() as f32 / 1_000_000.
+    }
+
+    /// Finish the frame started by `begin` at `now`, and return when the
+    /// next one should start. A frame that overran starts the next one
+    /// straight away instead of trying to catch up with a burst.
+    pub fn end(&mut self, now: Instant) -> Instant {
+        let start = self.start.unwrap_or(now);
+        let busy = now.saturating_duration_since(start);
+        let next = start + self.interval;
+        let late = now > next;
+        self.stats.record(busy, late);
+
+        if late {
+            now
+        } else {
+            next
+        }
+    }
+
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Return the stats so far and start counting again
+    pub fn take_stats(&mut self) -> FrameStats {
+        core::mem::take(&mut self.stats)
+    }
+}
+
+/// How long frames took to render and send
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameStats {
+    pub frames: u32,
+    /// Frames that took longer than the interval
+    pub late: u32,
+    /// Time spent rendering and sending every frame together
+    pub busy: Duration,
+    /// The longest any one frame took
+    pub worst: Duration,
+}
+
+impl FrameStats {
+    pub const fn new() -> Self {
+        Self {
+            frames: 0,
+            late: 0,
+            busy: Duration::from_ticks(0),
+            worst: Duration::from_ticks(0),
+        }
+    }
+
+    pub fn record(&mut self, busy: Duration, late: bool) {
+        self.frames += 1;
+        self.late += late as u32;
+        self.busy += busy;
+        self.worst = self.worst.max(busy);
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.frames == 0 {
+            Duration::from_ticks(0)
+        } else {
+            self.busy / self.frames
+        }
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
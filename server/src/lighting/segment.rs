@@ -0,0 +1,163 @@
+//! A run of LEDs on a strip with its own color, brightness and stack of
+//! animation layers
+use core::ops::Range;
+
+use log::info;
+
+use crate::lighting::AnimationEnum;
+use crate::lighting::Fade;
+use crate::lighting::Layer;
+use crate::lighting::Message;
+use crate::lighting::State;
+use crate::lighting::DEFAULT_TRANSITION;
+use crate::lighting::MAX_LAYERS;
+use crate::Blend;
+use crate::Color;
+
+/// How many segments a strip can be split into
+pub const MAX_SEGMENTS: usize = 8;
+
+pub struct Segment {
+    /// First LED of the segment
+    pub start: usize,
+    /// One past the last LED of the segment. Can be past the end of the
+    /// strip, segments get cut off there.
+    pub end: usize,
+    pub state: State,
+    /// Drawn bottom to top, each blended over the ones before it
+    layers: [Option<Layer>; MAX_LAYERS],
+    /// Which layer `UseAnimation` and `SetLayer` apply to
+    selected_layer: usize,
+    animation_speed: f32,
+    /// `state.base_color` and `state.brightness` follow these every frame
+    color: Fade<Color>,
+    brightness: Fade<f32>,
+    /// How long color, brightness and animation changes take, in seconds
+    transition: f32,
+    /// Whether anything has been shown yet. Until then there's nothing to
+    /// fade from, so changes apply straight away.
+    shown: bool,
+}
+
+impl Segment {
+    /// Starts out with a single opaque layer of solid color
+    pub fn new(start: usize, end: usize, seed: u64) -> Self {
+        let state = State::new(seed);
+        let mut layers = [const { None }; MAX_LAYERS];
+        layers[0] = Some(Layer::new(Blend::Normal, Fade::new(1.)));
+
+        Self {
+            start,
+            end,
+            layers,
+            selected_layer: 0,
+            animation_speed: 1.0,
+            color: Fade::new(state.base_color),
+            brightness: Fade::new(state.brightness),
+            transition: DEFAULT_TRANSITION,
+            shown: false,
+            state,
+        }
+    }
+
+    /// The LEDs this segment covers on a strip `length` LEDs long
+    pub fn range(&self, length: usize) -> Range<usize> {
+        self.start.min(length)..self.end.min(length)
+    }
+
+    /// Handle the messages that only concern a single segment
+    pub fn handle(&mut self, message: Message, length: usize) {
+        let transition = if self.shown { self.transition } else { 0. };
+        let segment_length = self.range(length).len();
+
+        match message {
+            Message::SetColor(c) => {
+                self.color.set(c, transition);
+            }
+            Message::SetTemperature(kelvin) => {
+                self.color.set(Color::from_kelvin(kelvin), transition);
+            }
+            Message::SetBrightness(b) => {
+                self.brightness.set((b as f32) / 255., transition);
+            }
+            Message::SetTransition(millis) => {
+                self.transition = millis as f32 / 1000.;
+            }
+            Message::SetSkip(s) => {
+                self.state.skip = s;
+            }
+            Message::SelectLayer(id) if (id as usize) < MAX_LAYERS => {
+                self.selected_layer = id as usize;
+            }
+            Message::SelectLayer(id) => {
+                info!("[lighting] no layer {id}");
+            }
+            Message::SetLayer(_, 0) => {
+                self.layers[self.selected_layer] = None;
+            }
+            Message::SetLayer(blend, opacity) => {
+                let opacity = opacity as f32 / 255.;
+                match &mut self.layers[self.selected_layer] {
+                    Some(layer) => {
+                        layer.blend = blend;
+                        layer.opacity.set(opacity, transition);
+                    }
+                    None => {
+                        let mut fade = Fade::new(0.);
+                        fade.set(opacity, transition);
+                        self.layers[self.selected_layer] = Some(Layer::new(blend, fade));
+                    }
+                }
+            }
+            Message::UseAnimation(bytes) => match &mut self.layers[self.selected_layer] {
+                Some(layer) => {
+                    let state = &mut self.state;
+                    state.length = segment_length;
+                    layer.switch(transition, || AnimationEnum::from_bytes(bytes, state));
+                }
+                None => info!("[lighting] layer {} doesn't exist", self.selected_layer),
+            },
+            Message::SetAnimationSpeed(speed) => {
+                self.animation_speed = speed;
+            }
+            message => info!("[lighting] {message:?} doesn't apply to a segment"),
+        }
+
+        self.state.base_color = self.color.value();
+        self.state.brightness = self.brightness.value();
+    }
+
+    /// Draw this segment into `frame`, which only covers this segment.
+    /// `layer_frame` and `outgoing_frame` have to be at least as long as
+    /// `frame`, they're where each layer and any animation it's fading out
+    /// get drawn before being blended in.
+    pub fn render(
+        &mut self,
+        delta: f32,
+        frame: &mut [Color],
+        layer_frame: &mut [Color],
+        outgoing_frame: &mut [Color],
+    ) {
+        self.shown = true;
+        self.color.tick(delta);
+        self.brightness.tick(delta);
+        self.state.base_color = self.color.value();
+        self.state.brightness = self.brightness.value();
+        self.state.length = frame.len();
+
+        let animation_delta = delta * self.animation_speed;
+        let layer_frame = &mut layer_frame[..frame.len()];
+
+        frame.fill(Color::BLACK);
+        for layer in self.layers.iter_mut().flatten() {
+            layer.draw(
+                delta,
+                animation_delta,
+                &mut self.state,
+                layer_frame,
+                outgoing_frame,
+            );
+            layer.composite(frame, layer_frame);
+        }
+    }
+}
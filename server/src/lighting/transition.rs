@@ -0,0 +1,71 @@
+//! Values that ease from one setting to the next instead of jumping
+use crate::Color;
+
+/// How long changes take unless a segment is told otherwise, in seconds
+pub const DEFAULT_TRANSITION: f32 = 0.5;
+
+pub trait Lerp: Copy {
+    /// `t` of the way from `self` to `other`
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Color::lerp(self, other, t)
+    }
+}
+
+/// A value on its way to a target, driven by real time rather than
+/// animation speed
+#[derive(Clone, Copy, Debug)]
+pub struct Fade<T> {
+    from: T,
+    to: T,
+    /// Seconds since the fade started
+    elapsed: f32,
+    /// Seconds the whole fade takes
+    duration: f32,
+}
+
+impl<T: Lerp> Fade<T> {
+    /// Already settled on `value`
+    pub const fn new(value: T) -> Self {
+        Self {
+            from: value,
+            to: value,
+            elapsed: 0.,
+            duration: 0.,
+        }
+    }
+
+    /// Head for `to` over `duration` seconds, starting from wherever the
+    /// fade is now so that retargeting mid-fade doesn't jump
+    pub fn set(&mut self, to: T, duration: f32) {
+        self.from = self.value();
+        self.to = to;
+        self.elapsed = 0.;
+        self.duration = duration.max(0.);
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+    }
+
+    pub fn value(&self) -> T {
+        if self.is_done() {
+            self.to
+        } else {
+            self.from.lerp(self.to, self.elapsed / self.duration)
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
@@ -1,16 +1,14 @@
+use crate::led::MAX_LEDS;
 use crate::lighting::Animation;
 use crate::lighting::State;
-use crate::lighting::NUM_LEDS;
 use crate::Color;
-use embassy_rp::clocks::RoscRng;
-use embassy_sync::blocking_mutex::raw::ThreadModeRawMutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::mutex::Mutex;
 use embassy_sync::mutex::MutexGuard;
 use half::f16;
 use log::info;
-use rand_core::RngCore;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 enum Star {
     Dead,
     Starting { target: f16, at: f16 },
@@ -67,37 +65,49 @@ impl Star {
     }
 }
 
-static STARS: Mutex<ThreadModeRawMutex, [Star; NUM_LEDS]> = Mutex::new([Star::Dead; NUM_LEDS]);
+/// How many twinkles can run at once, across every strip and segment
+const MAX_TWINKLES: usize = 8;
+
+/// Stars are kept out of `Twinkle` itself so they don't bloat every `AnimationEnum`
+static STARS: [Mutex<CriticalSectionRawMutex, [Star; MAX_LEDS]>; MAX_TWINKLES] =
+    [const { Mutex::new([Star::Dead; MAX_LEDS]) }; MAX_TWINKLES];
+
+/// Stars of `base_color` that brighten and fade at random. At speed 1.0 a
+/// star takes up to a second to brighten and as long again to fade.
+#[derive(Debug)]
 pub struct Twinkle {
-    stars: MutexGuard<'static, ThreadModeRawMutex, [Star; NUM_LEDS]>,
+    stars: MutexGuard<'static, CriticalSectionRawMutex, [Star; MAX_LEDS]>,
 }
 
 impl Twinkle {
-    pub fn new(star_count: u8) -> Self {
-        let mut stars = STARS.try_lock().unwrap();
-        *stars = [Star::Dead; NUM_LEDS];
+    /// Returns `None` if every set of stars is already in use
+    pub fn new(star_count: u8, state: &mut State) -> Option<Self> {
+        let mut stars = STARS.iter().find_map(|stars| stars.try_lock().ok())?;
+        *stars = [Star::Dead; MAX_LEDS];
 
-        let len = stars.len();
+        let len = state.length;
 
-        let mut rng = fastrand::Rng::with_seed(RoscRng.next_u64());
-        for _ in 0..star_count {
-            stars[rng.usize(0..len)] = Star::decaying(rng.f32());
+        // segments past the end of the strip have no LEDs to put stars on
+        if len != 0 {
+            for _ in 0..star_count {
+                stars[state.rng.usize(0..len)] = Star::decaying(state.rng.f32());
+            }
         }
 
-        Self { stars }
+        Some(Self { stars })
     }
 }
 
 impl Animation for Twinkle {
-    async fn animate(&mut self, delta: f32, state: &mut State) {
-        let mut rng = fastrand::Rng::with_seed(RoscRng.next_u64());
-        let mut colors = [Color::BLACK; NUM_LEDS];
+    fn animate(&mut self, delta: f32, state: &mut State, frame: &mut [Color]) {
+        let rng = &mut state.rng;
+        let len = frame.len();
 
         #[allow(clippy::needless_range_loop)]
-        for idx in 0..self.stars.len() {
+        for idx in 0..len {
             if self.stars[idx].tick(delta) {
                 loop {
-                    let dead_idx = rng.usize(0..self.stars.len());
+                    let dead_idx = rng.usize(0..len);
                     if self.stars[dead_idx].is_dead() {
                         self.stars[dead_idx] = Star::starting(rng.f32().max(0.1));
                         break;
@@ -105,13 +115,7 @@ impl Animation for Twinkle {
                 }
             }
 
-            colors[idx] = state
-                .base_color
-                .dim(self.stars[idx].brightness() * state.brightness);
-        }
-
-        for color in colors {
-            state.driver.send_color(color).await;
+            frame[idx] = state.base_color.dim(self.stars[idx].brightness());
         }
     }
 }
@@ -3,9 +3,11 @@
 #![deny(unused_must_use)]
 
 use core::fmt::Write;
+use core::sync::atomic::AtomicU16;
 
 use embassy_executor::Executor;
 use embassy_futures::join::join;
+use embassy_futures::join::join4;
 use embassy_futures::select::select;
 use embassy_rp::multicore::Stack;
 
@@ -18,9 +20,11 @@ use log::info;
 use mansion_lighting::lighting::Message;
 
 use bt_hci::controller::ExternalController;
+use embassy_rp::clocks::RoscRng;
 use embassy_rp::peripherals::USB;
 use embassy_rp::usb::Driver;
 use embassy_time::Timer;
+use rand_core::RngCore;
 use static_cell::ConstStaticCell;
 use static_cell::StaticCell;
 
@@ -47,8 +51,12 @@ use ssd1306::I2CDisplayInterface;
 use ssd1306::{prelude::*, Ssd1306};
 
 use mansion_lighting::blue;
+use mansion_lighting::config::Storage;
 use mansion_lighting::led::LedDriver;
+use mansion_lighting::led::MAX_LEDS;
+use mansion_lighting::led::MAX_STRIPS;
 use mansion_lighting::lighting;
+use mansion_lighting::lighting::Lighting;
 
 // Bind interrupts to their handlers.
 bind_interrupts!(struct Irqs {
@@ -58,20 +66,47 @@ bind_interrupts!(struct Irqs {
     PIO1_IRQ_0 => PIOInterruptHandler<PIO1>;
 });
 
-static CORE1_STACK: ConstStaticCell<Stack<4096>> = ConstStaticCell::new(Stack::new());
+/// Core1 runs the lighting executor and nothing else. Every strip's
+/// `Lighting` is about 10.5KB, so they're built on core0 and kept in statics,
+/// never on this stack; what's left is the executor, the lighting task's
+/// futures and each frame's locals, which take well under half of this even
+/// in debug builds.
+static CORE1_STACK: ConstStaticCell<Stack<8192>> = ConstStaticCell::new(Stack::new());
 static EXECUTOR1: StaticCell<Executor> = StaticCell::new();
 
+/// Estimated draw of every strip in milliamps, written by the lighting tasks
+/// and read over BLE
+static DRAW: [AtomicU16; MAX_STRIPS] = [const { AtomicU16::new(0) }; MAX_STRIPS];
+
 #[embassy_executor::task]
 async fn logger_task(driver: Driver<'static, USB>) {
     embassy_usb_logger::run!(1024, log::LevelFilter::Info, driver);
 }
 
+type LightingReceiver = Receiver<'static, CriticalSectionRawMutex, Message, 1>;
+
+/// The lighting state of the strip on state machine `SM` of PIO1
+type Strip<const SM: usize> = Lighting<LedDriver<'static, PIO1, SM>>;
+
+/// Every strip runs on its own state machine of PIO1, with its own lighting state
 #[embassy_executor::task]
 async fn lighting_task(
-    led_driver: LedDriver<'static, PIO1, 0>,
-    recv: Receiver<'static, CriticalSectionRawMutex, Message, 1>,
+    strips: (
+        &'static mut Strip<0>,
+        &'static mut Strip<1>,
+        &'static mut Strip<2>,
+        &'static mut Strip<3>,
+    ),
+    recvs: [LightingReceiver; MAX_STRIPS],
 ) -> ! {
-    lighting::run(led_driver, recv).await;
+    join4(
+        lighting::run(strips.0, recvs[0], &DRAW[0]),
+        lighting::run(strips.1, recvs[1], &DRAW[1]),
+        lighting::run(strips.2, recvs[2], &DRAW[2]),
+        lighting::run(strips.3, recvs[3], &DRAW[3]),
+    )
+    .await
+    .0
 }
 
 #[embassy_executor::main]
@@ -96,22 +131,68 @@ async fn main(spawner: Spawner) {
     display.clear().unwrap();
     let _ = write!(display, "Hello, world!");
 
+    let mut storage = Storage::new(p.FLASH);
+    let config = storage.load();
+    info!("loaded config {config:?}");
+
     let mut pio = Pio::new(p.PIO1, Irqs);
 
-    // initialize the w2812 LEDs
-    let leds = { LedDriver::new(&mut pio.common, pio.sm0, p.PIN_28) };
+    // initialize the LEDs, one strip per state machine, each fed by its own
+    // DMA channel. DMA_CH0 is the bluetooth chip's.
+    let [buf0, buf1, buf2, buf3] = {
+        static LED_BUFFERS: ConstStaticCell<[[u32; MAX_LEDS]; MAX_STRIPS]> =
+            ConstStaticCell::new([[0; MAX_LEDS]; MAX_STRIPS]);
+        LED_BUFFERS.take()
+    };
+    let common = &mut pio.common;
+    let formats = config.formats;
+    let leds = (
+        LedDriver::new(common, pio.sm0, p.PIN_28, p.DMA_CH1, buf0, formats[0]),
+        LedDriver::new(common, pio.sm1, p.PIN_27, p.DMA_CH2, buf1, formats[1]),
+        LedDriver::new(common, pio.sm2, p.PIN_26, p.DMA_CH3, buf2, formats[2]),
+        LedDriver::new(common, pio.sm3, p.PIN_22, p.DMA_CH4, buf3, formats[3]),
+    );
+
+    let lighting_channels: &[_; MAX_STRIPS] = {
+        static LIGHTING_CHANNELS: ConstStaticCell<
+            [Channel<CriticalSectionRawMutex, Message, 1>; MAX_STRIPS],
+        > = ConstStaticCell::new([const { Channel::new() }; MAX_STRIPS]);
+        LIGHTING_CHANNELS.take()
+    };
 
-    let lighting_channel = {
-        static LIGHTING_CHANNEL: ConstStaticCell<Channel<CriticalSectionRawMutex, Message, 1>> =
-            ConstStaticCell::new(Channel::new());
-        LIGHTING_CHANNEL.take()
+    // the ring oscillator is the only entropy we have, so seed animations from it once
+    let seeds = [(); MAX_STRIPS].map(|_| RoscRng.next_u64());
+
+    // too big for core1's stack, see `CORE1_STACK`
+    let strips = {
+        static STRIP0: StaticCell<Strip<0>> = StaticCell::new();
+        static STRIP1: StaticCell<Strip<1>> = StaticCell::new();
+        static STRIP2: StaticCell<Strip<2>> = StaticCell::new();
+        static STRIP3: StaticCell<Strip<3>> = StaticCell::new();
+        (
+            STRIP0.init_with(|| Lighting::new(leds.0, seeds[0])),
+            STRIP1.init_with(|| Lighting::new(leds.1, seeds[1])),
+            STRIP2.init_with(|| Lighting::new(leds.2, seeds[2])),
+            STRIP3.init_with(|| Lighting::new(leds.3, seeds[3])),
+        )
     };
 
-    let recv = lighting_channel.receiver();
+    let recvs = lighting_channels.each_ref().map(Channel::receiver);
     embassy_rp::multicore::spawn_core1(p.CORE1, CORE1_STACK.take(), move || {
         let executor1 = EXECUTOR1.init(Executor::new());
-        executor1.run(|spawner| spawner.spawn(lighting_task(leds, recv)).unwrap());
+        executor1.run(|spawner| spawner.spawn(lighting_task(strips, recvs)).unwrap());
     });
+    for (idx, channel) in lighting_channels.iter().enumerate() {
+        channel.send(Message::SetLength(config.lengths[idx])).await;
+        channel.send(Message::SetCurve(config.curves[idx])).await;
+        channel
+            .send(Message::SetPowerBudget(config.budgets[idx]))
+            .await;
+        channel
+            .send(Message::SetWhiteBalance(config.white_balances[idx]))
+            .await;
+    }
+    let senders = lighting_channels.each_ref().map(Channel::sender);
 
     // initialize the bluetooth chip
     // first, lets get the firmware in here. we need this firmware to use
@@ -161,7 +242,7 @@ async fn main(spawner: Spawner) {
 
         select(
             join(control.init(clm), runner.run()), // run the cyw43 driver
-            blue::run(controller, lighting_channel.sender()), // run the ble driver
+            blue::run(controller, &senders, &DRAW, &mut storage), // run the ble driver
         )
         .await;
     }
@@ -0,0 +1,149 @@
+//! Renders an animation offline, so it can be reviewed without a strip.
+//!
+//! ```text
+//! render [--seconds N] [--fps N] [--scale N] [--seed N] [--command CMD]... <out.png | out.gif>
+//! ```
+//!
+//! A `.png` is a space-time image with one row per frame and one column per
+//! LED, a `.gif` plays the frames back in real time. Commands use the same
+//! syntax as the simulator and are applied in order before the first frame,
+//! e.g. `--command "color 255 80 0" --command "animation 1 20"`.
+use std::fs::File;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use image::codecs::gif::GifEncoder;
+use image::codecs::gif::Repeat;
+use image::Delay;
+use image::Rgb;
+use image::RgbImage;
+use image::Rgba;
+use image::RgbaImage;
+use mansion_lighting::led::RecordingSink;
+use mansion_lighting::lighting::Lighting;
+use mansion_lighting::lighting::Message;
+use mansion_lighting::Color;
+use mansion_simulator::command;
+
+struct Args {
+    seconds: f32,
+    fps: u32,
+    scale: u32,
+    seed: u64,
+    commands: Vec<Message>,
+    output: PathBuf,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut seconds = 5.0;
+        let mut fps = 30;
+        let mut scale = 4;
+        let mut seed = 0;
+        let mut commands = Vec::new();
+        let mut output = None;
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("`{arg}` needs a value"));
+            match arg.as_str() {
+                "--seconds" => seconds = number(&value()?)?,
+                "--fps" => fps = number(&value()?)?,
+                "--scale" => scale = number(&value()?)?,
+                "--seed" => seed = number(&value()?)?,
+                "--command" => commands.push(command::parse(&value()?)?),
+                _ if arg.starts_with("--") => return Err(format!("unknown option `{arg}`")),
+                _ if output.is_none() => output = Some(PathBuf::from(arg)),
+                _ => return Err(format!("unexpected argument `{arg}`")),
+            }
+        }
+
+        if fps == 0 || scale == 0 {
+            return Err("--fps and --scale must be at least 1".into());
+        }
+
+        Ok(Self {
+            seconds,
+            fps,
+            scale,
+            seed,
+            commands,
+            output: output.ok_or("missing output file")?,
+        })
+    }
+}
+
+fn number<T: std::str::FromStr>(arg: &str) -> Result<T, String> {
+    arg.parse()
+        .map_err(|_| format!("`{arg}` is not a valid number"))
+}
+
+fn render(args: &mut Args) -> Vec<Vec<Color>> {
+    let mut lighting = Lighting::new(RecordingSink::new(), args.seed);
+    for message in args.commands.drain(..) {
+        lighting.handle(message);
+    }
+
+    let count = (args.seconds * args.fps as f32).round() as usize;
+    let delta = 1.0 / args.fps as f32;
+    (0..count)
+        .map(|_| lighting.render(delta).to_vec())
+        .collect()
+}
+
+fn write_png(args: &Args, frames: &[Vec<Color>]) -> image::ImageResult<()> {
+    let scale = args.scale;
+    let width = frames.first().map_or(0, |frame| frame.len());
+    let image = RgbImage::from_fn(width as u32 * scale, frames.len() as u32 * scale, |x, y| {
+        let color = frames[(y / scale) as usize][(x / scale) as usize];
+        Rgb([color.red(), color.green(), color.blue()])
+    });
+
+    image.save(&args.output)
+}
+
+fn write_gif(args: &Args, frames: &[Vec<Color>]) -> image::ImageResult<()> {
+    let scale = args.scale;
+    let mut encoder = GifEncoder::new(File::create(&args.output)?);
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    let delay = Delay::from_numer_denom_ms(1000, args.fps);
+    encoder.encode_frames(frames.iter().map(|frame| {
+        let image = RgbaImage::from_fn(frame.len() as u32 * scale, scale, |x, _| {
+            let color = frame[(x / scale) as usize];
+            Rgba([color.red(), color.green(), color.blue(), 255])
+        });
+        image::Frame::from_parts(image, 0, 0, delay)
+    }))
+}
+
+fn main() -> ExitCode {
+    let mut args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}");
+            eprintln!(
+                "usage: render [--seconds N] [--fps N] [--scale N] [--seed N] [--command CMD]... <out.png | out.gif>"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let frames = render(&mut args);
+
+    let result = match args.output.extension().and_then(|e| e.to_str()) {
+        Some("png") => write_png(&args, &frames),
+        Some("gif") => write_gif(&args, &frames),
+        _ => {
+            eprintln!("error: output must be a .png or .gif");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
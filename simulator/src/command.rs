@@ -0,0 +1,156 @@
+//! Text form of [`Message`], one per line:
+//!
+//! ```text
+//! color 255 128 0
+//! kelvin 2700
+//! brightness 64
+//! skip 2
+//! speed 1.5
+//! transition 500
+//! fps 60
+//! animation 1 20
+//! length 60
+//! segment 1
+//! range 30 90
+//! layer 1
+//! blend add 128
+//! curve cie
+//! curve 2.2
+//! budget 400
+//! balance 255 220 180
+//! noop
+//! ```
+use mansion_lighting::lighting::Curve;
+use mansion_lighting::lighting::Message;
+use mansion_lighting::Blend;
+use mansion_lighting::Color;
+
+pub fn parse(line: &str) -> Result<Message, String> {
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next() else {
+        return Ok(Message::Noop);
+    };
+    let args: Vec<&str> = words.collect();
+
+    let message = match command {
+        "noop" => {
+            expect_args(&args, 0)?;
+            Message::Noop
+        }
+        "color" => {
+            expect_args(&args, 3)?;
+            Message::SetColor(Color::new(
+                number(args[0])?,
+                number(args[1])?,
+                number(args[2])?,
+            ))
+        }
+        "kelvin" => {
+            expect_args(&args, 1)?;
+            Message::SetTemperature(number(args[0])?)
+        }
+        "balance" => {
+            expect_args(&args, 3)?;
+            Message::SetWhiteBalance(Color::new(
+                number(args[0])?,
+                number(args[1])?,
+                number(args[2])?,
+            ))
+        }
+        "brightness" => {
+            expect_args(&args, 1)?;
+            Message::SetBrightness(number(args[0])?)
+        }
+        "skip" => {
+            expect_args(&args, 1)?;
+            Message::SetSkip(number(args[0])?)
+        }
+        "speed" => {
+            expect_args(&args, 1)?;
+            let speed: f32 = number(args[0])?;
+            if !speed.is_finite() {
+                return Err(format!("speed must be finite, got {speed}"));
+            }
+            Message::SetAnimationSpeed(speed)
+        }
+        "transition" => {
+            expect_args(&args, 1)?;
+            Message::SetTransition(number(args[0])?)
+        }
+        "fps" => {
+            expect_args(&args, 1)?;
+            Message::SetFrameRate(number(args[0])?)
+        }
+        "length" => {
+            expect_args(&args, 1)?;
+            Message::SetLength(number(args[0])?)
+        }
+        "segment" => {
+            expect_args(&args, 1)?;
+            Message::SelectSegment(number(args[0])?)
+        }
+        "range" => {
+            expect_args(&args, 2)?;
+            Message::SetSegment(number(args[0])?, number(args[1])?)
+        }
+        "layer" => {
+            expect_args(&args, 1)?;
+            Message::SelectLayer(number(args[0])?)
+        }
+        "blend" => {
+            expect_args(&args, 2)?;
+            let blend = match args[0] {
+                "normal" => Blend::Normal,
+                "add" => Blend::Add,
+                "multiply" => Blend::Multiply,
+                "screen" => Blend::Screen,
+                "max" => Blend::Max,
+                blend => return Err(format!("unknown blend mode `{blend}`")),
+            };
+            Message::SetLayer(blend, number(args[1])?)
+        }
+        "curve" => {
+            expect_args(&args, 1)?;
+            match args[0] {
+                "cie" => Message::SetCurve(Curve::Cie),
+                gamma => {
+                    let gamma: f32 = number(gamma)?;
+                    if !(gamma.is_finite() && gamma > 0.) {
+                        return Err(format!("gamma must be positive, got {gamma}"));
+                    }
+                    Message::SetCurve(Curve::Gamma(gamma))
+                }
+            }
+        }
+        "budget" => {
+            expect_args(&args, 1)?;
+            Message::SetPowerBudget(number(args[0])?)
+        }
+        "animation" => {
+            if args.is_empty() || args.len() > 16 {
+                return Err(format!("animation takes 1 to 16 bytes, got {}", args.len()));
+            }
+            let mut bytes = [0u8; 16];
+            for (byte, arg) in bytes.iter_mut().zip(&args) {
+                *byte = number(arg)?;
+            }
+            Message::UseAnimation(bytes)
+        }
+        _ => return Err(format!("unknown command `{command}`")),
+    };
+
+    Ok(message)
+}
+
+fn expect_args(args: &[&str], count: usize) -> Result<(), String> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(format!("expected {count} arguments, got {}", args.len()))
+    }
+}
+
+fn number<T: std::str::FromStr>(arg: &str) -> Result<T, String> {
+    arg.parse()
+        .map_err(|_| format!("`{arg}` is not a valid number"))
+}
@@ -0,0 +1,3 @@
+//! Host-side tools for working on the lighting code without a Pico
+pub mod command;
+pub mod terminal;
@@ -0,0 +1,76 @@
+//! Runs the real lighting task on the host and draws the strip in the terminal.
+//! Commands are read from stdin, see [`mansion_simulator::command`] for the syntax.
+//!
+//! Pass `--seed N` to get the same animation every run, otherwise the seed
+//! comes from the clock.
+use std::sync::atomic::AtomicU16;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use embassy_executor::Spawner;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_sync::channel::Receiver;
+use embassy_sync::channel::Sender;
+use mansion_lighting::lighting;
+use mansion_lighting::lighting::Lighting;
+use mansion_lighting::lighting::Message;
+use mansion_simulator::command;
+use mansion_simulator::terminal;
+use mansion_simulator::terminal::TerminalSink;
+
+type LightingChannel = Channel<CriticalSectionRawMutex, Message, 1>;
+
+static LIGHTING_CHANNEL: LightingChannel = Channel::new();
+static DRAW: AtomicU16 = AtomicU16::new(0);
+
+#[embassy_executor::task]
+async fn lighting_task(
+    seed: u64,
+    recv: Receiver<'static, CriticalSectionRawMutex, Message, 1>,
+) -> ! {
+    let mut lighting = Lighting::new(TerminalSink::new(), seed);
+    lighting::run(&mut lighting, recv, &DRAW).await;
+}
+
+fn seed() -> Result<u64, String> {
+    let mut args = std::env::args().skip(1);
+    match (args.next().as_deref(), args.next()) {
+        (None, _) => Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |t| t.as_nanos() as u64)),
+        (Some("--seed"), Some(seed)) => seed
+            .parse()
+            .map_err(|_| format!("`{seed}` is not a valid seed")),
+        _ => Err("usage: mansion-simulator [--seed N]".into()),
+    }
+}
+
+fn read_commands(sender: Sender<'static, CriticalSectionRawMutex, Message, 1>) {
+    for line in std::io::stdin().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        match command::parse(&line) {
+            Ok(message) => embassy_futures::block_on(sender.send(message)),
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let seed = match seed() {
+        Ok(seed) => seed,
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    terminal::setup();
+
+    std::thread::spawn(|| read_commands(LIGHTING_CHANNEL.sender()));
+    spawner.must_spawn(lighting_task(seed, LIGHTING_CHANNEL.receiver()));
+}
@@ -0,0 +1,61 @@
+//! Draws the strip as a row of truecolor blocks at the top of the terminal
+use std::io::Write;
+use std::time::Duration;
+use std::time::Instant;
+
+use mansion_lighting::led::PixelSink;
+use mansion_lighting::Color;
+
+/// The lighting task renders far faster than a terminal can keep up with
+const DRAW_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Clear the screen and keep everything but the first two lines scrolling,
+/// so typed commands never push the strip off screen.
+pub fn setup() {
+    print!("\x1b[2J\x1b[3r\x1b[3;1H");
+    let _ = std::io::stdout().flush();
+}
+
+pub struct TerminalSink {
+    last_draw: Option<Instant>,
+}
+
+impl TerminalSink {
+    pub const fn new() -> Self {
+        Self { last_draw: None }
+    }
+}
+
+impl Default for TerminalSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PixelSink for TerminalSink {
+    async fn write(&mut self, pixels: &[Color]) {
+        if self
+            .last_draw
+            .is_some_and(|last| last.elapsed() < DRAW_INTERVAL)
+        {
+            return;
+        }
+        self.last_draw = Some(Instant::now());
+
+        // save the cursor, draw on the first line, then put the cursor back
+        let mut line = String::from("\x1b7\x1b[1;1H");
+        for color in pixels {
+            line += &format!(
+                "\x1b[38;2;{};{};{}m\u{2588}",
+                color.red(),
+                color.green(),
+                color.blue()
+            );
+        }
+        line += "\x1b[0m\x1b8";
+
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(line.as_bytes());
+        let _ = stdout.flush();
+    }
+}
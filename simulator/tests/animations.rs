@@ -0,0 +1,267 @@
+//! Behavior of individual animations that the snapshots don't spell out
+use mansion_lighting::Color;
+use mansion_lighting::Hsv;
+
+mod common;
+use common::close;
+use common::lighting;
+
+#[test]
+fn plasma_only_dims_without_hue_spread() {
+    let mut lighting = lighting(&["color 255 128 0", "animation 2 8 2 0"]);
+    let frame = lighting.render(0.5).to_vec();
+
+    for pixel in &frame {
+        assert!(pixel.red() >= pixel.green(), "{pixel:?}");
+        assert_eq!(pixel.blue(), 0, "{pixel:?}");
+    }
+    // blobs, not a flat color
+    assert!(frame.iter().any(|p| p.red() > 200));
+    assert!(frame.iter().any(|p| p.red() < 100));
+}
+
+#[test]
+fn plasma_turns_the_hue() {
+    // about 45 degrees either side of green, which doesn't wrap round
+    let mut lighting = lighting(&["color 0 255 0", "animation 2 8 1 64"]);
+    let frame = lighting.render(0.5).to_vec();
+
+    let hues = frame
+        .iter()
+        .filter(|p| **p != Color::BLACK)
+        .map(|p| Hsv::from(*p).hue);
+    let (min, max) = hues.fold((360f32, 0f32), |(min, max), h| (min.min(h), max.max(h)));
+    assert!(max - min > 20., "hues only span {min}-{max}");
+    assert!(min > 70. && max < 170., "hues span {min}-{max}");
+}
+
+#[test]
+fn plasma_moves_over_time() {
+    let mut lighting = lighting(&["color 255 255 255", "animation 2"]);
+    let before = lighting.render(0.).to_vec();
+    let after = lighting.render(1.).to_vec();
+    assert_ne!(before, after);
+}
+
+#[test]
+fn plasma_is_the_same_for_the_same_seed() {
+    let render = || {
+        let mut lighting = lighting(&["color 0 255 0", "animation 2 4 3 40"]);
+        lighting.render(0.25).to_vec()
+    };
+    assert_eq!(render(), render());
+}
+
+#[test]
+fn rainbow_spans_the_wheel() {
+    let mut lighting = lighting(&["length 12", "animation 3"]);
+    let frame = lighting.render(1.).to_vec();
+
+    // 30 degrees apart, starting from the white base color's hue of red
+    let hues: Vec<f32> = frame.iter().map(|p| Hsv::from(*p).hue).collect();
+    for (idx, hue) in hues.iter().enumerate() {
+        assert!((hue - idx as f32 * 30.).abs() < 2., "{hues:?}");
+    }
+}
+
+#[test]
+fn rainbow_reverses() {
+    let mut lighting = lighting(&["length 12", "animation 3 0 0 1"]);
+    let frame = lighting.render(0.).to_vec();
+    assert!((Hsv::from(frame[1]).hue - 330.).abs() < 2., "{frame:?}");
+}
+
+#[test]
+fn rainbow_scrolls_along_the_strip() {
+    let mut lighting = lighting(&["length 10", "animation 3 1"]);
+    let before = lighting.render(0.).to_vec();
+    // a second turns the hue 36 degrees, which is one LED's worth
+    let after = lighting.render(1.).to_vec();
+    for (after, before) in after[1..].iter().zip(&before) {
+        assert!(close(*after, *before), "{after:?} {before:?}");
+    }
+}
+
+#[test]
+fn rainbow_cycle_is_one_color() {
+    let mut lighting = lighting(&["color 0 255 0", "animation 3 2 0 0 128"]);
+    let frame = lighting.render(2.5).to_vec();
+    assert!(frame.iter().all(|p| *p == frame[0]));
+
+    // a quarter of the way round from green, and half saturated
+    let hsv = Hsv::from(frame[0]);
+    assert!((hsv.hue - 30.).abs() < 2., "{hsv:?}");
+    assert!((hsv.saturation - 0.5).abs() < 0.02, "{hsv:?}");
+}
+
+#[test]
+fn rainbow_respects_brightness_and_skip() {
+    let full = lighting(&["animation 3"]).render(0.).to_vec();
+    let dimmed = lighting(&["brightness 128", "skip 1", "animation 3"])
+        .render(0.)
+        .to_vec();
+
+    for (idx, (full, dimmed)) in full.iter().zip(&dimmed).enumerate() {
+        // skipping starts with the first LED
+        if idx % 2 == 0 {
+            assert_eq!(*dimmed, Color::BLACK);
+        } else {
+            let expected = full.red().max(full.green()).max(full.blue()) / 2;
+            let actual = dimmed.red().max(dimmed.green()).max(dimmed.blue());
+            assert!(actual.abs_diff(expected) <= 1, "{idx}: {full:?} {dimmed:?}");
+        }
+    }
+}
+
+/// Where the brightest LEDs are, brightest first
+fn brightest(frame: &[Color], count: usize) -> Vec<usize> {
+    let mut leds: Vec<usize> = (0..frame.len()).collect();
+    leds.sort_by_key(|idx| std::cmp::Reverse(frame[*idx].red()));
+    leds.truncate(count);
+    leds
+}
+
+#[test]
+fn comet_tail_fades_behind_the_head() {
+    let mut lighting = lighting(&["length 20", "color 255 0 0", "animation 4 1 8"]);
+    // 30 LEDs a second puts the head on LED 6
+    let frame = lighting.render(0.2).to_vec();
+
+    assert_eq!(frame[6], Color::RED);
+    for idx in 0..6 {
+        assert!(frame[idx].red() < frame[idx + 1].red(), "{frame:?}");
+    }
+    // the last of the tail has wrapped round to the far end, and there's
+    // nothing in front of the head
+    assert!(frame[19].red() > 0, "{frame:?}");
+    assert!(frame[7..19].iter().all(|p| *p == Color::BLACK), "{frame:?}");
+}
+
+#[test]
+fn comet_wraps_round() {
+    let mut lighting = lighting(&["length 20", "color 255 0 0", "animation 4 1 8"]);
+    let frame = lighting.render(0.).to_vec();
+    assert_eq!(frame[0], Color::RED);
+    // the tail is at the far end
+    assert!(frame[19].red() > frame[18].red(), "{frame:?}");
+    assert!(frame[18].red() > 0, "{frame:?}");
+}
+
+#[test]
+fn comet_reverses() {
+    let mut lighting = lighting(&["length 20", "color 255 0 0", "animation 4 1 8 1"]);
+    let frame = lighting.render(0.2).to_vec();
+    assert_eq!(brightest(&frame, 2), [13, 14]);
+}
+
+#[test]
+fn comet_bounces_off_the_end() {
+    let mut lighting = lighting(&["length 10", "color 255 0 0", "animation 4 1 4 0 1"]);
+    // 12 LEDs along a strip 10 long is on the way back, at LED 6, with the
+    // tail behind it towards the end
+    let frame = lighting.render(0.4).to_vec();
+    assert_eq!(brightest(&frame, 3), [6, 7, 8]);
+}
+
+#[test]
+fn comet_heads_are_spread_out() {
+    let mut lighting = lighting(&["length 30", "color 255 0 0", "animation 4 3 2"]);
+    let frame = lighting.render(0.).to_vec();
+    let mut heads = brightest(&frame, 3);
+    heads.sort();
+    assert_eq!(heads, [0, 10, 20]);
+}
+
+#[test]
+fn comet_follows_the_animation_speed() {
+    let mut lighting = lighting(&["length 30", "speed 2", "animation 4"]);
+    let frame = lighting.render(0.2).to_vec();
+    assert_eq!(brightest(&frame, 1), [12]);
+}
+
+/// How much light there is in all of `frame` together
+fn total(frame: &[Color]) -> u32 {
+    frame
+        .iter()
+        .map(|p| p.red() as u32 + p.green() as u32 + p.blue() as u32)
+        .sum()
+}
+
+// There are only a few sets of heat to go round, so keep to a handful of
+// fire tests, which run at the same time
+
+#[test]
+fn fire_rises_from_the_start() {
+    let mut lighting = lighting(&["length 60", "animation 5"]);
+    assert_eq!(total(lighting.render(0.)), 0);
+
+    let mut frame = Vec::new();
+    for _ in 0..60 {
+        frame = lighting.render(1. / 60.).to_vec();
+    }
+
+    assert!(total(&frame[..15]) > total(&frame[45..]), "{frame:?}");
+    // fire colors only
+    for pixel in &frame {
+        assert!(pixel.red() >= pixel.green() && pixel.green() >= pixel.blue());
+    }
+}
+
+#[test]
+fn fire_mirrors() {
+    let mut lighting = lighting(&["length 41", "animation 5 0 0 1"]);
+    for _ in 0..60 {
+        let frame = lighting.render(1. / 60.);
+        let mirrored: Vec<Color> = frame.iter().rev().copied().collect();
+        assert_eq!(frame, mirrored);
+    }
+}
+
+#[test]
+fn fire_cools_faster() {
+    let heat = |cooling: &str| {
+        let mut lighting = lighting(&["length 60", &format!("animation 5 {cooling} 200")]);
+        (0..120)
+            .map(|_| total(lighting.render(1. / 60.)))
+            .sum::<u32>()
+    };
+    assert!(heat("20") > heat("150"));
+}
+
+#[test]
+fn breathe_pulses_between_levels() {
+    // 2s a breath, between 20% and 100%
+    let mut lighting = lighting(&["color 255 0 0", "animation 6 20 51 0 1"]);
+
+    let bottom = lighting.render(0.).to_vec();
+    assert!(bottom.iter().all(|p| *p == bottom[0]));
+    assert!(bottom[0].red().abs_diff(51) <= 1, "{:?}", bottom[0]);
+
+    let quarter = lighting.render(0.5)[0];
+    assert!(quarter.red().abs_diff(153) <= 1, "{quarter:?}");
+    let top = lighting.render(0.5)[0];
+    assert_eq!(top, Color::RED);
+    let back = lighting.render(1.)[0];
+    assert_eq!(back, bottom[0]);
+}
+
+#[test]
+fn breathe_follows_the_animation_speed() {
+    let mut lighting = lighting(&["color 255 0 0", "speed 2", "animation 6 20 0 0 1"]);
+    lighting.render(0.);
+    assert_eq!(lighting.render(0.5)[0], Color::RED);
+}
+
+#[test]
+fn breathe_easing_changes_the_curve() {
+    let quarter = |easing: &str| {
+        let mut lighting = lighting(&["color 255 0 0", &format!("animation 6 40 0 0 {easing}")]);
+        lighting.render(0.);
+        lighting.render(1.)[0].red()
+    };
+    // halfway up a 4s breath
+    let (sine, linear, exponential) = (quarter("0"), quarter("1"), quarter("2"));
+    assert!(sine.abs_diff(128) <= 1, "{sine}");
+    assert!(linear.abs_diff(128) <= 1, "{linear}");
+    assert!(exponential < 80, "{exponential}");
+}
@@ -0,0 +1,80 @@
+//! Mixing colors and sampling gradients
+use mansion_lighting::Blend;
+use mansion_lighting::Color;
+use mansion_lighting::Palette;
+use mansion_lighting::Stop;
+
+const ORANGE: Color = Color::new(255, 128, 0);
+const GRAY: Color = Color::new(128, 128, 128);
+
+#[test]
+fn lerp_ends_and_middle() {
+    assert_eq!(Color::RED.lerp(Color::BLUE, 0.), Color::RED);
+    assert_eq!(Color::RED.lerp(Color::BLUE, 1.), Color::BLUE);
+    assert_eq!(
+        Color::BLACK.lerp(Color::WHITE, 0.5),
+        Color::new(127, 127, 127)
+    );
+
+    // out of range clamps
+    assert_eq!(Color::RED.lerp(Color::BLUE, -1.), Color::RED);
+    assert_eq!(Color::RED.lerp(Color::BLUE, 2.), Color::BLUE);
+}
+
+#[test]
+fn blend_modes() {
+    assert_eq!(ORANGE.saturating_add(GRAY), Color::new(255, 255, 128));
+    assert_eq!(ORANGE.multiply(Color::WHITE), ORANGE);
+    assert_eq!(ORANGE.multiply(Color::BLACK), Color::BLACK);
+    assert_eq!(ORANGE.multiply(GRAY), Color::new(128, 64, 0));
+    assert_eq!(ORANGE.screen(Color::BLACK), ORANGE);
+    assert_eq!(ORANGE.screen(Color::WHITE), Color::WHITE);
+    assert_eq!(ORANGE.screen(GRAY), Color::new(255, 191, 128));
+    assert_eq!(ORANGE.max(GRAY), Color::new(255, 128, 128));
+}
+
+#[test]
+fn blend_enum_matches_methods() {
+    assert_eq!(Blend::Normal.apply(ORANGE, GRAY), GRAY);
+    assert_eq!(Blend::Add.apply(ORANGE, GRAY), ORANGE.saturating_add(GRAY));
+    assert_eq!(Blend::Multiply.apply(ORANGE, GRAY), ORANGE.multiply(GRAY));
+    assert_eq!(Blend::Screen.apply(ORANGE, GRAY), ORANGE.screen(GRAY));
+    assert_eq!(Blend::Max.apply(ORANGE, GRAY), ORANGE.max(GRAY));
+}
+
+#[test]
+fn palette_samples_between_stops() {
+    let palette = Palette::even([Color::RED, Color::GREEN, Color::BLUE]);
+    assert_eq!(palette.sample(0.), Color::RED);
+    assert_eq!(palette.sample(0.5), Color::GREEN);
+    assert_eq!(palette.sample(1.), Color::BLUE);
+    assert_eq!(palette.sample(0.25), Color::RED.lerp(Color::GREEN, 0.5));
+    assert_eq!(palette.sample(-3.), Color::RED);
+    assert_eq!(palette.sample(3.), Color::BLUE);
+}
+
+#[test]
+fn palette_holds_before_first_and_after_last_stop() {
+    let palette = Palette::new([Stop::new(0.25, Color::RED), Stop::new(0.75, Color::BLUE)]);
+    assert_eq!(palette.sample(0.1), Color::RED);
+    assert_eq!(palette.sample(0.9), Color::BLUE);
+    assert_eq!(palette.sample(0.5), Color::RED.lerp(Color::BLUE, 0.5));
+}
+
+#[test]
+fn palette_wraps() {
+    let palette = Palette::new([Stop::new(0.25, Color::RED), Stop::new(0.75, Color::BLUE)]);
+    // halfway round the gap from the last stop back to the first
+    let seam = Color::BLUE.lerp(Color::RED, 0.5);
+    assert_eq!(palette.sample_wrapping(0.), seam);
+    assert_eq!(palette.sample_wrapping(1.), seam);
+    assert_eq!(palette.sample_wrapping(1.5), palette.sample(0.5));
+    assert_eq!(palette.sample_wrapping(-0.5), palette.sample(0.5));
+}
+
+#[test]
+fn single_stop_palette() {
+    let palette = Palette::even([ORANGE]);
+    assert_eq!(palette.sample(0.3), ORANGE);
+    assert_eq!(palette.sample_wrapping(0.3), ORANGE);
+}
@@ -0,0 +1,121 @@
+//! Conversions between `Color` and the hue based and perceptual color models
+use mansion_lighting::Color;
+use mansion_lighting::Hsl;
+use mansion_lighting::Hsv;
+use mansion_lighting::Oklab;
+use mansion_lighting::Oklch;
+
+/// A spread of colors, including grays and both ends of every channel
+fn colors() -> impl Iterator<Item = Color> {
+    const LEVELS: [u8; 6] = [0, 1, 64, 128, 200, 255];
+    LEVELS.into_iter().flat_map(|red| {
+        LEVELS.into_iter().flat_map(move |green| {
+            LEVELS
+                .into_iter()
+                .map(move |blue| Color::new(red, green, blue))
+        })
+    })
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+#[test]
+fn round_trips() {
+    for color in colors() {
+        assert_eq!(Color::from(Hsv::from(color)), color, "hsv");
+        assert_eq!(Color::from(Hsl::from(color)), color, "hsl");
+        assert_eq!(Color::from(Oklab::from(color)), color, "oklab");
+        assert_eq!(Color::from(Oklch::from(color)), color, "oklch");
+    }
+}
+
+#[test]
+fn hsv_primaries() {
+    assert_eq!(Color::from(Hsv::new(0., 1., 1.)), Color::RED);
+    assert_eq!(Color::from(Hsv::new(120., 1., 1.)), Color::GREEN);
+    assert_eq!(Color::from(Hsv::new(240., 1., 1.)), Color::BLUE);
+    assert_eq!(Color::from(Hsv::new(360., 1., 1.)), Color::RED);
+    assert_eq!(Color::from(Hsv::new(-60., 1., 1.)), Color::PURPLE);
+    assert_eq!(Color::from(Hsv::new(200., 0., 1.)), Color::WHITE);
+
+    let yellow = Hsv::from(Color::YELLOW);
+    assert!(close(yellow.hue, 60.) && close(yellow.saturation, 1.) && close(yellow.value, 1.));
+}
+
+#[test]
+fn hsl_primaries() {
+    assert_eq!(Color::from(Hsl::new(0., 1., 0.5)), Color::RED);
+    assert_eq!(Color::from(Hsl::new(180., 1., 0.5)), Color::CYAN);
+    assert_eq!(Color::from(Hsl::new(0., 1., 1.)), Color::WHITE);
+    assert_eq!(Color::from(Hsl::new(0., 1., 0.)), Color::BLACK);
+}
+
+#[test]
+fn oklab_reference_values() {
+    // from the reference implementation
+    let white = Oklab::from(Color::WHITE);
+    assert!(
+        close(white.l, 1.) && close(white.a, 0.) && close(white.b, 0.),
+        "{white:?}"
+    );
+
+    let red = Oklch::from(Color::RED);
+    assert!(close(red.l, 0.628) && close(red.c, 0.2577), "{red:?}");
+    assert!((red.h - 29.23).abs() < 0.1, "{red:?}");
+
+    assert_eq!(Oklch::from(Color::BLACK).c, 0.);
+}
+
+#[test]
+fn rotation_wraps() {
+    assert!(close(Hsv::new(350., 1., 1.).rotate(20.).hue, 10.));
+    assert!(close(Hsl::new(10., 1., 0.5).rotate(-20.).hue, 350.));
+    assert!(close(Oklch::new(0.5, 0.1, 90.).rotate(720.).h, 90.));
+
+    // rotating keeps lightness and chroma
+    let red = Oklch::from(Color::RED);
+    let turned = red.rotate(120.);
+    assert_eq!((turned.l, turned.c), (red.l, red.c));
+}
+
+#[test]
+fn hue_takes_the_short_way_round() {
+    let from = Hsv::new(350., 1., 1.);
+    let to = Hsv::new(30., 1., 1.);
+    assert!(close(from.lerp(to, 0.5).hue, 10.));
+    assert!(close(to.lerp(from, 0.25).hue, 20.));
+
+    let from = Oklch::new(0.6, 0.1, 300.);
+    let to = Oklch::new(0.6, 0.1, 20.);
+    assert!(close(from.lerp(to, 0.5).h, 340.));
+}
+
+#[test]
+fn grays_take_the_other_hue() {
+    let gray = Hsv::from(Color::new(128, 128, 128));
+    let blue = Hsv::from(Color::BLUE);
+    assert!(close(gray.lerp(blue, 0.1).hue, 240.));
+
+    let white = Oklch::from(Color::WHITE);
+    let blue = Oklch::from(Color::BLUE);
+    assert!(close(white.lerp(blue, 0.5).h, blue.h));
+}
+
+#[test]
+fn oklab_blend_stays_bright() {
+    // halfway from red to green in RGB is a dark olive, OKLab keeps it lighter
+    let lab = Oklab::from(Color::RED).lerp(Oklab::from(Color::GREEN), 0.5);
+    let rgb_mid = Oklab::from(Color::new(128, 128, 0));
+    assert!(lab.l > rgb_mid.l, "{lab:?} vs {rgb_mid:?}");
+
+    assert_eq!(
+        Color::from(Oklab::from(Color::RED).lerp(Oklab::from(Color::BLUE), 0.)),
+        Color::RED
+    );
+    assert_eq!(
+        Color::from(Oklab::from(Color::RED).lerp(Oklab::from(Color::BLUE), 1.)),
+        Color::BLUE
+    );
+}
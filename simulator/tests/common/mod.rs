@@ -0,0 +1,25 @@
+//! Fixtures shared by the integration tests. Each test file only uses some
+//! of them, so the rest would otherwise be dead code there.
+#![allow(dead_code)]
+
+use mansion_lighting::led::RecordingSink;
+use mansion_lighting::lighting::Lighting;
+use mansion_lighting::Color;
+use mansion_simulator::command;
+
+/// A strip that shows exactly what it's told: no power limit and no gamma,
+/// then `commands` in simulator syntax
+pub fn lighting(commands: &[&str]) -> Lighting<RecordingSink> {
+    let mut lighting = Lighting::new(RecordingSink::new(), 0);
+    for line in ["budget 0", "curve 1.0"].iter().chain(commands) {
+        lighting.handle(command::parse(line).unwrap());
+    }
+    lighting
+}
+
+/// Equal but for rounding
+pub fn close(a: Color, b: Color) -> bool {
+    a.red().abs_diff(b.red()) <= 1
+        && a.green().abs_diff(b.green()) <= 1
+        && a.blue().abs_diff(b.blue()) <= 1
+}
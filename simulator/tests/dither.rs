@@ -0,0 +1,71 @@
+//! Dithering and the 16 bit gamma table, which together decide how dim LEDs fade
+use mansion_lighting::lighting::Curve;
+use mansion_lighting::lighting::Dither;
+use mansion_lighting::lighting::GammaTable;
+use mansion_lighting::Color;
+
+/// Sum of the red channel over `frames` frames of a constant `level`
+fn red_over(frames: u32, level: u16) -> u32 {
+    let mut dither = Dither::new();
+    (0..frames)
+        .map(|_| dither.quantize(0, [level, 0, 0]).red() as u32)
+        .sum()
+}
+
+#[test]
+fn whole_steps_pass_through() {
+    let mut dither = Dither::new();
+    for _ in 0..4 {
+        assert_eq!(
+            dither.quantize(0, [0x1200, 0xff00, 0]),
+            Color::new(0x12, 0xff, 0)
+        );
+    }
+}
+
+#[test]
+fn sub_step_levels_average_out() {
+    // a quarter of the lowest step lights up every fourth frame
+    assert_eq!(red_over(256, 0x0040), 64);
+    // and between two steps alternates between them
+    assert_eq!(red_over(256, 0x1080), 0x10 * 256 + 128);
+
+    for level in [1, 0x33, 0x80, 0x1ff, 0x7777, u16::MAX] {
+        // over 256 frames the 8 bit sum is the 16 bit level, short of full on
+        let expected = level.min(0xff00) as u32;
+        let actual = red_over(256, level);
+        assert!(actual.abs_diff(expected) <= 1, "{level:#x}: {actual}");
+    }
+}
+
+#[test]
+fn leds_dither_independently() {
+    let mut dither = Dither::new();
+    dither.quantize(0, [0x80, 0x80, 0x80]);
+    assert_eq!(dither.quantize(1, [0x80, 0, 0]), Color::BLACK);
+    assert_eq!(dither.quantize(0, [0x80, 0, 0]), Color::new(1, 0, 0));
+}
+
+#[test]
+fn gamma_table_ends_and_is_monotonic() {
+    for curve in [Curve::Cie, Curve::Gamma(1.0), Curve::Gamma(2.2)] {
+        let table = GammaTable::new(curve);
+        assert_eq!(table.correct(0), 0, "{curve:?}");
+        assert!(table.correct(u16::MAX) > 0xff00, "{curve:?}");
+
+        let mut previous = 0;
+        for level in 0..=u16::MAX {
+            let corrected = table.correct(level);
+            assert!(corrected >= previous, "{curve:?} at {level:#x}");
+            previous = corrected;
+        }
+    }
+}
+
+#[test]
+fn cie_keeps_dim_levels() {
+    // the darkest 8 bit input is well below one output step, which is what
+    // dithering is there for
+    let level = GammaTable::new(Curve::Cie).correct(0x0101);
+    assert!(level > 0 && level < 0x100, "{level:#x}");
+}
@@ -0,0 +1,67 @@
+//! Easing curves and the pulses made from them
+use mansion_lighting::lighting::Easing;
+
+const ALL: [Easing; 3] = [Easing::Sine, Easing::Linear, Easing::Exponential];
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn curves_go_from_0_to_1() {
+    for easing in ALL {
+        assert!(close(easing.ease(0.), 0.), "{easing:?}");
+        assert!(close(easing.ease(1.), 1.), "{easing:?}");
+        // and no further
+        assert!(close(easing.ease(-1.), 0.), "{easing:?}");
+        assert!(close(easing.ease(2.), 1.), "{easing:?}");
+    }
+}
+
+#[test]
+fn curves_only_go_up() {
+    for easing in ALL {
+        let mut last = 0.;
+        for step in 1..=100 {
+            let value = easing.ease(step as f32 / 100.);
+            assert!(value >= last, "{easing:?} at {step}");
+            last = value;
+        }
+    }
+}
+
+#[test]
+fn curve_shapes() {
+    assert!(close(Easing::Linear.ease(0.25), 0.25));
+    assert!(close(Easing::Sine.ease(0.5), 0.5));
+    // slow to start
+    assert!(Easing::Sine.ease(0.1) < 0.1);
+    // and slower still
+    assert!(Easing::Exponential.ease(0.5) < 0.3);
+}
+
+#[test]
+fn pulses_go_up_and_back_down() {
+    for easing in ALL {
+        assert!(close(easing.pulse(0.), 0.), "{easing:?}");
+        assert!(close(easing.pulse(0.5), 1.), "{easing:?}");
+        assert!(close(easing.pulse(0.2), easing.pulse(0.8)), "{easing:?}");
+        assert!(close(easing.pulse(0.3), easing.ease(0.6)), "{easing:?}");
+    }
+}
+
+#[test]
+fn pulses_wrap() {
+    for easing in ALL {
+        assert!(close(easing.pulse(1.25), easing.pulse(0.25)), "{easing:?}");
+        assert!(close(easing.pulse(-0.25), easing.pulse(0.75)), "{easing:?}");
+    }
+}
+
+#[test]
+fn from_byte() {
+    assert_eq!(Easing::from_byte(0), Some(Easing::Sine));
+    assert_eq!(Easing::from_byte(1), Some(Easing::Linear));
+    assert_eq!(Easing::from_byte(2), Some(Easing::Exponential));
+    assert_eq!(Easing::from_byte(3), None);
+}
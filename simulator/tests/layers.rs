@@ -0,0 +1,82 @@
+//! Stacking animations in layers within a segment
+use mansion_lighting::lighting::Message;
+use mansion_lighting::lighting::MAX_LAYERS;
+use mansion_lighting::Blend;
+use mansion_lighting::Color;
+
+mod common;
+use common::lighting;
+
+fn first_pixel(commands: &[&str]) -> Color {
+    lighting(commands).render(0.)[0]
+}
+
+#[test]
+fn a_single_layer_is_unchanged() {
+    assert_eq!(first_pixel(&["color 255 128 0"]), Color::new(255, 128, 0));
+}
+
+#[test]
+fn layers_blend_over_the_ones_below() {
+    // every layer shares the segment's color, so a layer of the same
+    // color shows the blend mode itself
+    let cases = [
+        ("normal", Color::new(200, 100, 0)),
+        ("add", Color::new(255, 200, 0)),
+        ("multiply", Color::new(157, 40, 0)),
+        ("screen", Color::new(243, 161, 0)),
+        ("max", Color::new(200, 100, 0)),
+    ];
+
+    for (blend, expected) in cases {
+        let blend = format!("blend {blend} 255");
+        let actual = first_pixel(&["color 200 100 0", "layer 1", &blend]);
+        assert_eq!(actual, expected, "{blend}");
+    }
+}
+
+#[test]
+fn opacity_mixes_the_layer_in() {
+    // half of black multiplied over gray is a darker gray
+    let actual = first_pixel(&["color 128 128 128", "layer 1", "blend multiply 128"]);
+    assert!(actual.red().abs_diff(96) <= 1, "{actual:?}");
+}
+
+#[test]
+fn zero_opacity_removes_a_layer() {
+    let plain = first_pixel(&["color 10 20 30"]);
+    let removed = first_pixel(&["color 10 20 30", "layer 1", "blend add 255", "blend add 0"]);
+    assert_eq!(removed, plain);
+
+    // even the base layer, which leaves the segment dark
+    assert_eq!(first_pixel(&["blend normal 0"]), Color::BLACK);
+}
+
+#[test]
+fn animations_go_on_the_selected_layer() {
+    // a starless twinkle is black, so adding it changes nothing
+    let pixel = first_pixel(&[
+        "color 10 20 30",
+        "layer 1",
+        "blend add 255",
+        "animation 1 0",
+    ]);
+    assert_eq!(pixel, Color::new(10, 20, 30));
+
+    // but on the base layer it replaces the color
+    assert_eq!(
+        first_pixel(&["color 10 20 30", "animation 1 0"]),
+        Color::BLACK
+    );
+}
+
+#[test]
+fn missing_layers_ignore_animations() {
+    let mut lighting = lighting(&["layer 2"]);
+    lighting.handle(Message::UseAnimation([1; 16]));
+    assert_eq!(lighting.render(0.)[0], Color::WHITE);
+
+    lighting.handle(Message::SelectLayer(MAX_LAYERS as u8));
+    lighting.handle(Message::SetLayer(Blend::Multiply, 255));
+    assert_eq!(lighting.render(0.)[0], Color::WHITE);
+}
@@ -0,0 +1,82 @@
+//! Packing colors for the PIO, which has to be bit-exact for every strip type
+use embassy_time::Duration;
+use mansion_lighting::led::ChannelOrder;
+use mansion_lighting::led::PixelFormat;
+use mansion_lighting::led::MAX_LEDS;
+use mansion_lighting::led::RESET_TIME;
+use mansion_lighting::lighting::MAX_FPS;
+use mansion_lighting::Color;
+
+const COLOR: Color = Color::new(0x11, 0x22, 0x33);
+
+#[test]
+fn ws2812_is_grb() {
+    assert_eq!(PixelFormat::WS2812.pack(COLOR), 0x22_11_33_00);
+    assert_eq!(PixelFormat::WS2812.bits(), 24);
+}
+
+#[test]
+fn channel_orders() {
+    let cases = [
+        (ChannelOrder::Rgb, 0x11_22_33_00),
+        (ChannelOrder::Rbg, 0x11_33_22_00),
+        (ChannelOrder::Grb, 0x22_11_33_00),
+        (ChannelOrder::Gbr, 0x22_33_11_00),
+        (ChannelOrder::Brg, 0x33_11_22_00),
+        (ChannelOrder::Bgr, 0x33_22_11_00),
+    ];
+
+    for (order, packed) in cases {
+        let format = PixelFormat { order, rgbw: false };
+        assert_eq!(format.pack(COLOR), packed, "{order:?}");
+    }
+}
+
+#[test]
+fn rgbw_extracts_white() {
+    assert_eq!(PixelFormat::SK6812.bits(), 32);
+    assert_eq!(PixelFormat::SK6812.pack(COLOR), 0x11_00_22_11);
+    assert_eq!(PixelFormat::SK6812.pack(Color::WHITE), 0x00_00_00_ff);
+    assert_eq!(PixelFormat::SK6812.pack(Color::RED), 0x00_ff_00_00);
+}
+
+#[test]
+fn byte_round_trip() {
+    for byte in 0..=u8::MAX {
+        if let Some(format) = PixelFormat::from_byte(byte) {
+            assert_eq!(format.to_byte(), byte);
+        }
+    }
+
+    assert_eq!(PixelFormat::from_byte(0x06), None);
+    assert_eq!(
+        PixelFormat::from_byte(PixelFormat::SK6812.to_byte()),
+        Some(PixelFormat::SK6812)
+    );
+}
+
+#[test]
+fn send_time() {
+    // timed by the slowest bit, a 1, at 11 cycles of the 8MHz PIO
+    assert_eq!(
+        PixelFormat::WS2812.send_time(1),
+        Duration::from_nanos(33_000)
+    );
+    assert_eq!(
+        PixelFormat::SK6812.send_time(1),
+        Duration::from_nanos(44_000)
+    );
+    assert_eq!(
+        PixelFormat::WS2812.send_time(MAX_LEDS),
+        Duration::from_micros(9_900)
+    );
+    assert_eq!(PixelFormat::WS2812.send_time(0), Duration::from_ticks(0));
+}
+
+#[test]
+fn a_full_strip_keeps_up_with_the_top_frame_rate() {
+    for format in (0..=u8::MAX).filter_map(PixelFormat::from_byte) {
+        let frame = format.send_time(MAX_LEDS) + RESET_TIME;
+        assert!(frame < Duration::from_hz(MAX_FPS as u64), "{format:?}");
+    }
+}
@@ -0,0 +1,24 @@
+//! Animations whose buffers come from a fixed pool. In their own test binary
+//! so that nothing else is holding on to any of it.
+use mansion_lighting::led::MAX_STRIPS;
+use mansion_lighting::lighting::AnimationEnum;
+use mansion_lighting::lighting::State;
+
+fn fire(state: &mut State) -> Option<AnimationEnum> {
+    let mut bytes = [0; 16];
+    bytes[0] = 5;
+    AnimationEnum::from_bytes(bytes, state)
+}
+
+#[test]
+fn every_strip_can_fade_one_fire_into_another() {
+    let mut state = State::new(0);
+    let mut fires: Vec<_> = (0..MAX_STRIPS * 2)
+        .map(|_| fire(&mut state).expect("ran out of fires"))
+        .collect();
+
+    // the pool's empty now, until one goes out
+    assert!(fire(&mut state).is_none());
+    fires.pop();
+    assert!(fire(&mut state).is_some());
+}
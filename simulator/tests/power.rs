@@ -0,0 +1,66 @@
+//! Current estimates and the power limit, which keep the strip from browning out the Pico
+mod common;
+
+use mansion_lighting::lighting::PowerLimit;
+use mansion_lighting::lighting::PowerModel;
+use mansion_simulator::command;
+
+const FULL: u16 = u16::MAX;
+
+#[test]
+fn estimate_follows_the_model() {
+    let model = PowerModel::WS2812;
+    assert_eq!(model.estimate(&[[0; 3]; 10]), 10_000);
+    assert_eq!(model.estimate(&[[FULL, 0, 0]]), 17_000);
+    assert_eq!(model.estimate(&[[FULL; 3]; 2]), 2 * 43_000);
+}
+
+#[test]
+fn under_budget_is_left_alone() {
+    let mut levels = [[FULL, 0x1234, 0]; 4];
+    let draw = PowerLimit::new(1000).limit(&mut levels);
+    assert_eq!(levels, [[FULL, 0x1234, 0]; 4]);
+    assert_eq!(draw, PowerModel::WS2812.estimate(&levels).div_ceil(1000));
+}
+
+#[test]
+fn over_budget_is_scaled_down() {
+    let mut levels = [[FULL; 3]; 90];
+    let draw = PowerLimit::new(400).limit(&mut levels);
+    assert!(draw <= 400, "{draw}");
+    assert!(draw > 390, "{draw}");
+
+    // everything is scaled by the same amount, so colors keep their hue
+    let [red, green, blue] = levels[0];
+    assert!(red == green && green == blue && red > 0);
+}
+
+#[test]
+fn zero_budget_means_no_limit() {
+    let mut levels = [[FULL; 3]; 90];
+    assert_eq!(PowerLimit::new(0).limit(&mut levels), 90 * 43);
+    assert_eq!(levels, [[FULL; 3]; 90]);
+}
+
+#[test]
+fn budget_too_small_for_idle_current_goes_dark() {
+    let mut levels = [[FULL; 3]; 90];
+    PowerLimit::new(50).limit(&mut levels);
+    assert_eq!(levels, [[0; 3]; 90]);
+
+    // a frame that's already dark is still over budget, from idle alone
+    let mut levels = [[0; 3]; 90];
+    assert_eq!(PowerLimit::new(50).limit(&mut levels), 90);
+    assert_eq!(levels, [[0; 3]; 90]);
+}
+
+#[test]
+fn lighting_reports_draw() {
+    let mut lighting = common::lighting(&["color 255 255 255", "budget 400"]);
+    lighting.render(0.);
+    assert!(lighting.draw() <= 400, "{}", lighting.draw());
+
+    lighting.handle(command::parse("budget 0").unwrap());
+    lighting.render(0.);
+    assert_eq!(lighting.draw(), 90 * 43);
+}
@@ -0,0 +1,67 @@
+//! Frame pacing and frame time stats
+use embassy_time::Duration;
+use embassy_time::Instant;
+use mansion_lighting::lighting::Scheduler;
+use mansion_lighting::lighting::MAX_FPS;
+
+fn at(millis: u64) -> Instant {
+    Instant::from_millis(millis)
+}
+
+#[test]
+fn frames_are_evenly_spaced() {
+    let mut scheduler = Scheduler::new(50);
+    assert_eq!(scheduler.interval(), Duration::from_millis(20));
+
+    assert_eq!(scheduler.begin(at(1000)), 0.);
+    // however long the frame took, the next one starts an interval later
+    assert_eq!(scheduler.end(at(1003)), at(1020));
+    assert_eq!(scheduler.begin(at(1020)), 0.02);
+    assert_eq!(scheduler.end(at(1035)), at(1040));
+}
+
+#[test]
+fn delta_is_real_time() {
+    let mut scheduler = Scheduler::new(50);
+    scheduler.begin(at(0));
+    scheduler.end(at(5));
+    // woken up late
+    assert_eq!(scheduler.begin(at(30)), 0.03);
+}
+
+#[test]
+fn late_frames_dont_burst() {
+    let mut scheduler = Scheduler::new(50);
+    scheduler.begin(at(0));
+    assert_eq!(scheduler.end(at(45)), at(45));
+    assert_eq!(scheduler.begin(at(45)), 0.045);
+    assert_eq!(scheduler.end(at(50)), at(65));
+    assert_eq!(scheduler.stats().late, 1);
+}
+
+#[test]
+fn fps_is_clamped() {
+    let mut scheduler = Scheduler::new(0);
+    assert_eq!(scheduler.interval(), Duration::from_secs(1));
+
+    scheduler.set_fps(u8::MAX);
+    assert_eq!(scheduler.interval(), Duration::from_hz(MAX_FPS as u64));
+}
+
+#[test]
+fn stats_add_up() {
+    let mut scheduler = Scheduler::new(50);
+    for (start, end) in [(0, 4), (20, 28), (40, 70), (70, 72)] {
+        scheduler.begin(at(start));
+        scheduler.end(at(end));
+    }
+
+    let stats = scheduler.take_stats();
+    assert_eq!(stats.frames, 4);
+    assert_eq!(stats.late, 1);
+    assert_eq!(stats.busy, Duration::from_millis(44));
+    assert_eq!(stats.worst, Duration::from_millis(30));
+    assert_eq!(stats.average(), Duration::from_millis(11));
+
+    assert_eq!(scheduler.stats().frames, 0);
+}
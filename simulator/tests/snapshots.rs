@@ -0,0 +1,161 @@
+//! Golden-frame snapshots of every animation.
+//!
+//! Each case renders a fixed number of frames with a fixed seed and compares
+//! them against `tests/snapshots/<case>.txt`, one line per frame and one hex
+//! color per LED. After an intentional change, re-bless them with
+//!
+//! ```text
+//! BLESS=1 cargo test --test snapshots
+//! ```
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use mansion_lighting::led::RecordingSink;
+use mansion_lighting::lighting::Lighting;
+use mansion_simulator::command;
+
+const FRAMES: usize = 60;
+const FPS: f32 = 30.;
+const SEED: u64 = 0;
+
+/// Every animation, with a handful of `State` parameters each
+const CASES: &[(&str, &[&str])] = &[
+    ("solid", &[]),
+    ("solid_unlimited", &["budget 0"]),
+    ("solid_short", &["color 0 255 0", "length 12"]),
+    ("solid_dim", &["color 255 128 0", "brightness 64"]),
+    ("solid_skip", &["color 0 0 255", "skip 2"]),
+    (
+        "solid_gamma",
+        &["color 255 128 0", "brightness 64", "curve 2.2"],
+    ),
+    ("twinkle", &["animation 1 20"]),
+    (
+        "twinkle_slow",
+        &["color 255 80 0", "speed 0.5", "animation 1 20"],
+    ),
+    (
+        "twinkle_dim_skip",
+        &["brightness 100", "skip 1", "speed 2", "animation 1 40"],
+    ),
+    ("twinkle_short", &["length 30", "speed 4", "animation 1 10"]),
+    ("plasma", &["color 255 0 0", "animation 2"]),
+    (
+        "plasma_detail",
+        &["color 0 160 255", "speed 2", "animation 2 6 4 64"],
+    ),
+    ("rainbow", &["animation 3"]),
+    (
+        "rainbow_scroll",
+        &["brightness 128", "skip 1", "speed 4", "animation 3 1 128"],
+    ),
+    (
+        "rainbow_cycle",
+        &["color 0 0 255", "speed 8", "animation 3 2 0 1 160"],
+    ),
+    ("comet", &["color 255 120 0", "animation 4"]),
+    (
+        "comet_bounce",
+        &["length 40", "speed 2", "animation 4 3 12 1 1"],
+    ),
+    ("fire", &["brightness 200", "animation 5"]),
+    ("fire_mirrored", &["length 50", "animation 5 80 160 1"]),
+    ("breathe", &["color 0 200 255", "animation 6 10"]),
+    (
+        "breathe_exponential",
+        &["color 255 60 0", "skip 1", "animation 6 15 30 200 2"],
+    ),
+    (
+        "layers",
+        &[
+            "color 0 40 120",
+            "layer 1",
+            "blend add 255",
+            "speed 4",
+            "animation 1 20",
+            "layer 2",
+            "blend multiply 128",
+        ],
+    ),
+    (
+        "segments",
+        &[
+            "range 0 30",
+            "color 255 0 0",
+            "skip 1",
+            "segment 1",
+            "range 30 60",
+            "color 0 0 255",
+            "speed 4",
+            "animation 1 10",
+            "segment 2",
+            "range 75 200",
+            "brightness 128",
+        ],
+    ),
+];
+
+fn render(commands: &[&str]) -> String {
+    let mut lighting = Lighting::new(RecordingSink::new(), SEED);
+    for line in commands {
+        lighting.handle(command::parse(line).unwrap());
+    }
+
+    let mut out = String::new();
+    for _ in 0..FRAMES {
+        let frame = lighting.render(1. / FPS);
+        let pixels: Vec<String> = frame
+            .iter()
+            .map(|c| format!("{:02x}{:02x}{:02x}", c.red(), c.green(), c.blue()))
+            .collect();
+        writeln!(out, "{}", pixels.join(" ")).unwrap();
+    }
+
+    out
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"))
+}
+
+// A single test, since animations like `Twinkle` can't have two instances
+// alive at once.
+#[test]
+fn snapshots() {
+    let bless = std::env::var_os("BLESS").is_some();
+    let mut failures = Vec::new();
+
+    for (name, commands) in CASES {
+        let actual = render(commands);
+        let path = golden_path(name);
+
+        if bless {
+            std::fs::write(&path, &actual).unwrap();
+            continue;
+        }
+
+        let Ok(expected) = std::fs::read_to_string(&path) else {
+            failures.push(format!("{name}: missing {}", path.display()));
+            continue;
+        };
+
+        if let Some((idx, _)) = actual
+            .lines()
+            .zip(expected.lines())
+            .enumerate()
+            .find(|(_, (a, e))| a != e)
+        {
+            failures.push(format!("{name}: frame {idx} differs"));
+        } else if actual.lines().count() != expected.lines().count() {
+            failures.push(format!("{name}: frame count differs"));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "snapshots don't match (re-bless with BLESS=1 if this is intended):\n{}",
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,69 @@
+//! Color temperature and white balance
+mod common;
+
+use mansion_lighting::lighting::Message;
+use mansion_lighting::Color;
+
+#[test]
+fn known_temperatures() {
+    assert_eq!(Color::from_kelvin(1000), Color::new(255, 68, 0));
+    assert_eq!(Color::from_kelvin(2700), Color::new(255, 167, 87));
+    assert_eq!(Color::from_kelvin(6600), Color::new(255, 255, 255));
+    assert_eq!(Color::from_kelvin(10000), Color::new(202, 218, 255));
+}
+
+#[test]
+fn out_of_range_clamps() {
+    assert_eq!(Color::from_kelvin(0), Color::from_kelvin(Color::MIN_KELVIN));
+    assert_eq!(
+        Color::from_kelvin(u16::MAX),
+        Color::from_kelvin(Color::MAX_KELVIN)
+    );
+}
+
+#[test]
+fn warmer_is_less_blue() {
+    let mut previous = Color::from_kelvin(1800);
+    for kelvin in (1900..=6500).step_by(100) {
+        let color = Color::from_kelvin(kelvin);
+        assert!(color.blue() >= previous.blue(), "{kelvin}K");
+        assert!(color.green() >= previous.green(), "{kelvin}K");
+        previous = color;
+    }
+}
+
+fn render(messages: impl IntoIterator<Item = Message>) -> Color {
+    let mut lighting = common::lighting(&[]);
+    for message in messages {
+        lighting.handle(message);
+    }
+    lighting.render(0.)[0]
+}
+
+#[test]
+fn temperature_sets_the_base_color() {
+    assert_eq!(
+        render([Message::SetTemperature(2700)]),
+        render([Message::SetColor(Color::from_kelvin(2700))])
+    );
+}
+
+#[test]
+fn white_balance_scales_channels() {
+    assert_eq!(
+        render([Message::SetWhiteBalance(Color::WHITE)]),
+        Color::WHITE
+    );
+    assert_eq!(
+        render([Message::SetWhiteBalance(Color::new(255, 0, 128))]),
+        Color::new(255, 0, 128)
+    );
+    // a channel the balance turns off stays off, whatever the color
+    assert_eq!(
+        render([
+            Message::SetColor(Color::new(0, 0, 255)),
+            Message::SetWhiteBalance(Color::new(0, 0, 0)),
+        ]),
+        Color::BLACK
+    );
+}
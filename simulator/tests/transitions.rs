@@ -0,0 +1,95 @@
+//! Fading between colors, brightnesses and animations
+use mansion_lighting::led::RecordingSink;
+use mansion_lighting::lighting::Fade;
+use mansion_lighting::lighting::Lighting;
+use mansion_lighting::lighting::Message;
+use mansion_lighting::Color;
+
+mod common;
+use common::close;
+
+/// With a transition of a second
+fn lighting() -> Lighting<RecordingSink> {
+    common::lighting(&["transition 1000"])
+}
+
+#[test]
+fn fade_reaches_its_target() {
+    let mut fade = Fade::new(0.);
+    fade.set(1., 2.);
+    assert_eq!(fade.value(), 0.);
+    fade.tick(0.5);
+    assert_eq!(fade.value(), 0.25);
+    fade.tick(10.);
+    assert_eq!(fade.value(), 1.);
+    assert!(fade.is_done());
+}
+
+#[test]
+fn retargeting_starts_from_where_it_is() {
+    let mut fade = Fade::new(0.);
+    fade.set(1., 1.);
+    fade.tick(0.5);
+    fade.set(0., 1.);
+    assert_eq!(fade.value(), 0.5);
+    fade.tick(0.5);
+    assert_eq!(fade.value(), 0.25);
+}
+
+#[test]
+fn changes_before_the_first_frame_are_instant() {
+    let mut lighting = lighting();
+    lighting.handle(Message::SetColor(Color::RED));
+    assert_eq!(lighting.render(0.)[0], Color::RED);
+}
+
+#[test]
+fn color_fades() {
+    let mut lighting = lighting();
+    lighting.render(0.);
+    lighting.handle(Message::SetColor(Color::BLACK));
+
+    let halfway = lighting.render(0.5)[0];
+    assert!(close(halfway, Color::new(128, 128, 128)), "{halfway:?}");
+    assert_eq!(lighting.render(0.5)[0], Color::BLACK);
+}
+
+#[test]
+fn brightness_fades() {
+    let mut lighting = lighting();
+    lighting.render(0.);
+    lighting.handle(Message::SetBrightness(0));
+
+    let quarter = lighting.render(0.75)[0];
+    assert!(close(quarter, Color::new(64, 64, 64)), "{quarter:?}");
+    assert_eq!(lighting.render(0.25)[0], Color::BLACK);
+}
+
+#[test]
+fn zero_transition_snaps() {
+    let mut lighting = lighting();
+    lighting.handle(Message::SetTransition(0));
+    lighting.render(0.);
+    lighting.handle(Message::SetColor(Color::BLUE));
+    assert_eq!(lighting.render(0.)[0], Color::BLUE);
+}
+
+#[test]
+fn animations_crossfade() {
+    let mut lighting = lighting();
+    lighting.render(0.);
+
+    // twinkle with no stars is all black, so halfway through it's half white
+    lighting.handle(Message::UseAnimation([
+        1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ]));
+    let halfway = lighting.render(0.5)[0];
+    assert!(close(halfway, Color::new(128, 128, 128)), "{halfway:?}");
+    assert_eq!(lighting.render(0.5)[0], Color::BLACK);
+
+    // and back again
+    lighting.handle(Message::UseAnimation([0; 16]));
+    let halfway = lighting.render(0.5)[0];
+    assert!(close(halfway, Color::new(128, 128, 128)), "{halfway:?}");
+    assert_eq!(lighting.render(0.5)[0], Color::WHITE);
+}